@@ -1,3 +1,10 @@
+//! Command line interface of the `wucc` binary.
+
+use crate::{
+  compilers::options::{CodegenOptions, CppStandard, IncludeGuard},
+  hexdump::{GenerateOptions, Language},
+  version::OutputFormat,
+};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -20,7 +27,8 @@ pub enum Subcommand {
   YamlToCpp(YamlToCppCompileArgs),
   Embed(EmbedCompileArgs),
   Hexdump(HexdumpArgs),
-  Version(VersionArgs),
+  Version(Box<VersionArgs>),
+  Changelog(ChangelogArgs),
 
   #[clap(hide = true)]
   WhoIsTheBest,
@@ -44,6 +52,9 @@ pub struct JsonToCppCompileArgs {
   #[arg(short = 'o', long)]
   pub output_name: Option<String>,
 
+  #[command(flatten)]
+  pub codegen: CodegenArgs,
+
   /// Generate nlohmann::json object as well.
   #[arg(long)]
   pub nlohmann: bool,
@@ -67,6 +78,9 @@ pub struct YamlToCppCompileArgs {
   #[arg(short = 'o', long)]
   pub output_name: Option<String>,
 
+  #[command(flatten)]
+  pub codegen: CodegenArgs,
+
   /// Generate nlohmann::json object as well.
   #[arg(long)]
   pub nlohmann: bool,
@@ -93,6 +107,38 @@ pub struct EmbedCompileArgs {
   /// Output file name.
   #[arg(short = 'o', long)]
   pub output_name: Option<String>,
+
+  #[command(flatten)]
+  pub codegen: CodegenArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CodegenArgs {
+  /// List source files relative to this directory in generated files.
+  #[arg(long, value_name = "DIR")]
+  pub source_root: Option<PathBuf>,
+
+  /// C++ standard the generated code must compile with.
+  #[arg(long = "std", value_enum, default_value_t = CppStandard::Cpp20)]
+  pub standard: CppStandard,
+
+  /// Include guard style of generated headers.
+  #[arg(long, value_enum, default_value_t = IncludeGuard::Pragma)]
+  pub include_guard: IncludeGuard,
+
+  /// Check that every generated file compiles on its own with a local C++ compiler (see `CXX`).
+  #[arg(long)]
+  pub verify: bool,
+}
+
+impl CodegenArgs {
+  pub fn options(&self) -> CodegenOptions {
+    CodegenOptions {
+      source_root: self.source_root.clone(),
+      standard: self.standard,
+      include_guard: self.include_guard,
+    }
+  }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -180,6 +226,23 @@ pub struct HexdumpGenerateArgs {
   pub line_size: Option<u64>,
 }
 
+impl HexdumpGenerateArgs {
+  pub fn options(&self) -> GenerateOptions {
+    GenerateOptions {
+      language: match self.template {
+        HexdumpGenerateFormat::C => Language::C,
+        HexdumpGenerateFormat::Cpp => Language::Cpp,
+        HexdumpGenerateFormat::Rust => Language::Rust,
+        HexdumpGenerateFormat::Python => Language::Python,
+      },
+      prefix: self.prefix.clone(),
+      suffix: self.suffix.clone(),
+      separator: self.separator.clone(),
+      bytes_per_line: self.line_size.map(|size| size as usize),
+    }
+  }
+}
+
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum HexdumpFormat {
   Hex,
@@ -245,4 +308,187 @@ pub struct VersionArgs {
   /// Bump version major.
   #[arg(short = 'M', long = "bump-major", conflicts_with_all = ["show", "assign", "bump_patch", "bump_minor"])]
   pub bump_major: bool,
+
+  /// Pick the bump from the Conventional Commits since the last version tag: major for breaking
+  /// changes, minor for features, patch for fixes.
+  #[arg(long = "auto", conflicts_with_all = ["show", "check", "assign", "bump_patch", "bump_minor", "bump_major"])]
+  pub auto: bool,
+
+  /// Make the new version a pre-release with this identifier (alpha, beta, rc, ...): counts up from
+  /// `<ID>.1`, and a release gets its patch bumped first.
+  #[arg(long = "pre", value_name = "ID", conflicts_with_all = ["show", "check", "assign", "release"])]
+  pub pre: Option<String>,
+
+  /// Drop pre-release and build metadata from the version.
+  #[arg(long = "release", conflicts_with_all = ["show", "check", "assign", "pre"])]
+  pub release: bool,
+
+  /// Attach build metadata to the new version.
+  #[arg(long = "build", value_name = "META", conflicts_with_all = ["show", "check"])]
+  pub build: Option<String>,
+
+  /// Output format of the version table.
+  #[arg(short = 'f', long = "format", value_enum, default_value_t = OutputFormat::Plain)]
+  pub format: OutputFormat,
+
+  /// Print only the resolved version: the current one, or the new one when assigning or bumping.
+  #[arg(short = 'g', long = "get", conflicts_with_all = ["show", "format"])]
+  pub get: bool,
+
+  /// Check that all version files agree and exit with an error if they do not.
+  #[arg(short = 'c', long = "check", conflicts_with_all = ["show", "assign", "bump_patch", "bump_minor", "bump_major", "get"])]
+  pub check: bool,
+
+  /// Show the changes as unified diffs without writing any file.
+  #[arg(short = 'n', long = "dry-run", conflicts_with_all = ["show", "check", "interactive"])]
+  pub dry_run: bool,
+
+  /// Show the changes to each file and ask before writing it.
+  #[arg(short = 'i', long = "interactive", conflicts_with_all = ["show", "check", "get", "format"])]
+  pub interactive: bool,
+
+  /// Add a section for the new version to a Keep a Changelog file (default: CHANGELOG.md), built
+  /// from the Conventional Commits since the last version tag.
+  #[arg(
+    long = "changelog",
+    value_name = "PATH",
+    num_args = 0..=1,
+    default_missing_value = "CHANGELOG.md",
+    conflicts_with_all = ["show", "check"]
+  )]
+  pub changelog: Option<PathBuf>,
+
+  /// Generate a C/C++ header with macros for the resolved version at PATH.
+  #[arg(
+    long = "emit-header",
+    value_name = "PATH",
+    requires = "prefix",
+    conflicts_with_all = ["show", "check"]
+  )]
+  pub emit_header: Option<PathBuf>,
+
+  /// Macro prefix of the generated header, e.g. `FOO` for `FOO_VERSION_MAJOR`.
+  #[arg(long = "prefix", value_name = "PREFIX", requires = "emit_header")]
+  pub prefix: Option<String>,
+
+  /// Add a `constexpr` C++ struct with the version in this namespace to the generated header.
+  #[arg(long = "header-namespace", value_name = "NAMESPACE", requires = "emit_header")]
+  pub header_namespace: Option<String>,
+
+  /// Include guard style of the generated header.
+  #[arg(long = "include-guard", value_enum, requires = "emit_header")]
+  pub include_guard: Option<IncludeGuard>,
+
+  /// Record the git commit and whether the working tree has changes in the generated header.
+  #[arg(long = "git-info", requires = "emit_header")]
+  pub git_info: bool,
+
+  /// Record the build date in the generated header (honors `SOURCE_DATE_EPOCH`).
+  #[arg(long = "build-date", requires = "emit_header")]
+  pub build_date: bool,
+
+  /// Commit the updated version files with git.
+  #[arg(long = "commit", conflicts_with_all = ["show", "check", "dry_run"])]
+  pub commit: bool,
+
+  /// Create an annotated `v<version>` tag on the version commit; `<component>/v<version>` with
+  /// --component.
+  #[arg(long = "tag", requires = "commit")]
+  pub tag: bool,
+
+  /// Message of the version commit and tag; `{version}` and `{tag}` are substituted.
+  #[arg(
+    long = "message",
+    value_name = "TEMPLATE",
+    default_value = "Bump version to {version}",
+    requires = "commit"
+  )]
+  pub message: String,
+
+  /// Commit even if tracked files have uncommitted changes.
+  #[arg(long = "force", requires = "commit")]
+  pub force: bool,
+
+  /// Handle this version file instead of detecting them, e.g. `cmake:lib/CMakeLists.txt`; repeatable.
+  #[arg(long = "file", value_name = "KIND:PATH", conflicts_with = "component")]
+  pub file: Vec<crate::version::Source>,
+
+  /// Only handle version files of these kinds.
+  #[arg(
+    long = "only",
+    value_name = "KIND",
+    value_delimiter = ',',
+    value_parser = clap::builder::PossibleValuesParser::new(crate::version::KINDS.map(|kind| kind.id))
+  )]
+  pub only: Vec<String>,
+
+  /// Leave version files of these kinds alone.
+  #[arg(
+    long = "exclude",
+    value_name = "KIND",
+    value_delimiter = ',',
+    value_parser = clap::builder::PossibleValuesParser::new(crate::version::KINDS.map(|kind| kind.id))
+  )]
+  pub exclude: Vec<String>,
+
+  /// Macro prefixes of the versions in export headers, e.g. `FOO` for `FOO_VERSION_MAJOR`; each is a version file of
+  /// its own, so a header can hold the versions of several libraries.
+  #[arg(long = "header-prefix", value_name = "PREFIX", value_delimiter = ',')]
+  pub header_prefix: Vec<String>,
+
+  /// File names of the export headers to look for, where `*` and `?` are wildcards (default: version.h).
+  #[arg(long = "header-name", value_name = "PATTERN", value_delimiter = ',')]
+  pub header_name: Vec<String>,
+
+  /// Handle every export header found, rather than the first one.
+  #[arg(long = "all-headers")]
+  pub all_headers: bool,
+
+  /// Configuration file declaring version sources (default: wucc.yml, when present).
+  #[arg(long = "config", value_name = "PATH")]
+  pub config: Option<PathBuf>,
+
+  /// Only handle the version files of this monorepo component: a directory name, or its path.
+  #[arg(long = "component", value_name = "NAME", conflicts_with = "changelog")]
+  pub component: Option<String>,
+
+  /// List the components of a monorepo with their version files and exit.
+  #[arg(
+    long = "components",
+    conflicts_with_all = ["show", "check", "assign", "bump_patch", "bump_minor", "bump_major", "auto", "pre", "release", "build", "get", "dry_run", "interactive", "changelog", "commit", "component"]
+  )]
+  pub components: bool,
+
+  /// File kind holding the reference version for --check (e.g. cargo); defaults to the highest version found.
+  #[arg(
+    short = 'r',
+    long = "reference",
+    value_name = "KIND",
+    requires = "check"
+  )]
+  pub reference: Option<String>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ChangelogArgs {
+  /// Changelog file to update.
+  #[arg(
+    short = 'o',
+    long = "output",
+    value_name = "PATH",
+    default_value = "CHANGELOG.md"
+  )]
+  pub output: PathBuf,
+
+  /// Title the section `Unreleased` instead of the current version.
+  #[arg(short = 'u', long = "unreleased")]
+  pub unreleased: bool,
+
+  /// Rebuild every section from the version tags, keeping the text above the first one.
+  #[arg(long = "regenerate")]
+  pub regenerate: bool,
+
+  /// Show the changes as a unified diff without writing the file.
+  #[arg(short = 'n', long = "dry-run")]
+  pub dry_run: bool,
 }
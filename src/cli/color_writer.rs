@@ -15,3 +15,20 @@ pub fn log_compiled_file(file: &Path, file_type: &str) {
       .bold()
   );
 }
+
+pub fn log_verified_file(file: &Path) {
+  println!(
+    "{} {}",
+    "Verified".green(),
+    file.display().to_string().bold()
+  );
+}
+
+pub fn log_verification_failure(file: &Path, diagnostics: &str) {
+  eprintln!(
+    "{} {}\n{}",
+    "Failed to verify".red(),
+    file.display().to_string().bold(),
+    diagnostics.trim_end()
+  );
+}
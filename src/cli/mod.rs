@@ -1,3 +1,102 @@
+//! The `wucc` command line: argument dispatch, progress output and error
+//! reports. Nothing here is reachable from the library API.
+
 mod color_writer;
 
-pub use self::color_writer::log_compiled_file;
\ No newline at end of file
+use self::color_writer::{log_compiled_file, log_verification_failure, log_verified_file};
+
+use crate::{Error, Result, args, compilers, hexdump, version};
+use colored::Colorize;
+use std::{path::PathBuf, process::ExitCode};
+
+/// Runs the command line of the process.
+pub fn run() -> ExitCode {
+  match dispatch(args::parse_args()) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(error) => {
+      report(&error);
+      ExitCode::from(error.exit_code())
+    }
+  }
+}
+
+fn dispatch(args: args::Args) -> Result<()> {
+  match args.subcommand {
+    args::Subcommand::JsonToCpp(a) => {
+      let c = compilers::json::JsonCompiler::new(a.namespace.clone(), &a.output_dir, a.nlohmann)
+        .with_options(a.codegen.options());
+
+      let header = c.compile(a.input.as_slice(), &a.output_name)?;
+      log_compiled_file(&header, "JSON");
+      if a.codegen.verify {
+        verify(&[header], a.codegen.standard)?;
+      }
+    }
+    args::Subcommand::YamlToCpp(a) => {
+      let c = compilers::yaml::YamlCompiler::new(a.namespace.clone(), &a.output_dir, a.nlohmann)
+        .with_options(a.codegen.options());
+
+      let header = c.compile(a.input.as_slice(), &a.output_name)?;
+      log_compiled_file(&header, "YAML");
+      if a.codegen.verify {
+        verify(&[header], a.codegen.standard)?;
+      }
+    }
+    args::Subcommand::Embed(a) => {
+      let c = compilers::embed::EmbedCompiler::new(a.namespace.clone(), &a.output_dir)
+        .with_options(a.codegen.options());
+
+      let (header, source) = c.compile(a.text.as_slice(), a.binary.as_slice(), &a.output_name)?;
+      log_compiled_file(&header, "Resource");
+      if a.codegen.verify {
+        verify(&[header, source], a.codegen.standard)?;
+      }
+    }
+    args::Subcommand::Hexdump(a) => hexdump::run(a)?,
+    args::Subcommand::Version(a) => version::run(*a)?,
+    args::Subcommand::Changelog(a) => version::changelog::run(a)?,
+    args::Subcommand::WhoIsTheBest => crate::misc::credits(),
+  }
+
+  Ok(())
+}
+
+/// Checks that the generated `files` compile and prints the outcome of each.
+fn verify(files: &[PathBuf], standard: compilers::options::CppStandard) -> Result<()> {
+  let report = compilers::verify::verify(files, standard)?;
+  if report.compiler.is_none() {
+    println!(
+      "{} {}",
+      "Skipped".yellow(),
+      "verification: no C++ compiler found (set CXX to choose one)".yellow()
+    );
+  }
+  for (file, diagnostics) in &report.files {
+    match diagnostics {
+      None => log_verified_file(file),
+      Some(diagnostics) => log_verification_failure(file, diagnostics),
+    }
+  }
+  report.into_result()
+}
+
+/// Prints `error` and everything that caused it to stderr.
+fn report(error: &Error) {
+  eprintln!("{} {}", "error:".red().bold(), error);
+  if let Error::VersionWrite { failures } = error {
+    for (name, failure) in failures {
+      eprintln!("  {}: {}", name, failure);
+      print_causes(failure, "    ");
+    }
+  } else {
+    print_causes(error, "  ");
+  }
+}
+
+fn print_causes(error: &dyn std::error::Error, indent: &str) {
+  let mut source = error.source();
+  while let Some(cause) = source {
+    eprintln!("{}caused by: {}", indent, cause);
+    source = cause.source();
+  }
+}
@@ -1,5 +1,5 @@
-use std::path::PathBuf;
-use anyhow::{Context, Result};
+use crate::error::{Error, Result};
+use std::path::{Component, Path, PathBuf};
 
 pub fn output_stem(files: &[PathBuf], output_name: &Option<String>) -> Result<String> {
   if let Some(name) = output_name {
@@ -10,10 +10,120 @@ pub fn output_stem(files: &[PathBuf], output_name: &Option<String>) -> Result<St
     .iter()
     .map(|f| {
       f.file_stem()
-        .context("Failed to get file stem")
-        .and_then(|s| s.to_str().context("Failed to convert stem to string"))
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| Error::InvalidFileName(f.clone()))
     })
-    .collect::<anyhow::Result<Vec<_>>>()?;
+    .collect::<Result<Vec<_>>>()?;
 
   Ok(stems.join("_"))
-}
\ No newline at end of file
+}
+
+/// Returns the file name of `file` with dots replaced, as used for getter names.
+pub fn identifier_name(file: &Path) -> Result<String> {
+  let name = file
+    .file_name()
+    .and_then(|s| s.to_str())
+    .map(|s| s.replace('.', "_"))
+    .ok_or_else(|| Error::InvalidFileName(file.to_path_buf()))?;
+  check_identifier(&name, || format!("file {}", file.display()))?;
+  Ok(name)
+}
+
+/// Returns the stem of `file`, checked to be usable as a C++ identifier.
+pub fn identifier_stem(file: &Path) -> Result<String> {
+  let stem = file
+    .file_stem()
+    .and_then(|s| s.to_str())
+    .ok_or_else(|| Error::InvalidFileName(file.to_path_buf()))?;
+  check_identifier(stem, || format!("file {}", file.display()))?;
+  Ok(stem.to_string())
+}
+
+/// Checks every `::`-separated part of `namespace`, which must not be empty.
+pub fn check_namespace(namespace: &str) -> Result<()> {
+  namespace
+    .split("::")
+    .try_for_each(|part| check_identifier(part, || format!("namespace '{}'", namespace)))
+}
+
+const CPP_KEYWORDS: &[&str] = &[
+  "alignas", "alignof", "and", "and_eq", "asm", "auto", "bitand", "bitor", "bool", "break", "case",
+  "catch", "char", "char8_t", "char16_t", "char32_t", "class", "compl", "concept", "const",
+  "consteval", "constexpr", "constinit", "const_cast", "continue", "co_await", "co_return",
+  "co_yield", "decltype", "default", "delete", "do", "double", "dynamic_cast", "else", "enum",
+  "explicit", "export", "extern", "false", "float", "for", "friend", "goto", "if", "inline", "int",
+  "long", "mutable", "namespace", "new", "noexcept", "not", "not_eq", "nullptr", "operator", "or",
+  "or_eq", "private", "protected", "public", "register", "reinterpret_cast", "requires", "return",
+  "short", "signed", "sizeof", "static", "static_assert", "static_cast", "struct", "switch",
+  "template", "this", "thread_local", "throw", "true", "try", "typedef", "typeid", "typename",
+  "union", "unsigned", "using", "virtual", "void", "volatile", "wchar_t", "while", "xor", "xor_eq",
+];
+
+pub(crate) fn check_identifier(name: &str, origin: impl FnOnce() -> String) -> Result<()> {
+  let mut chars = name.chars();
+  let valid = chars
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    && !CPP_KEYWORDS.contains(&name);
+
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::InvalidIdentifier {
+      identifier: name.to_string(),
+      origin: origin(),
+    })
+  }
+}
+
+/// Formats a source path for listing in generated files.
+///
+/// The path is made relative to `source_root` when it lies inside of it, and
+/// is always joined with forward slashes so the output does not depend on the
+/// host OS or on the directory the compiler was invoked from.
+pub fn display_path(path: &Path, source_root: Option<&Path>) -> String {
+  let relative = source_root.and_then(|root| relative_to(path, root));
+  normalize_separators(relative.as_deref().unwrap_or(path))
+}
+
+/// Returns display paths of `files`, sorted so the argument order does not matter.
+pub fn sorted_display_paths(files: &[PathBuf], source_root: Option<&Path>) -> Vec<String> {
+  let mut paths: Vec<String> = files.iter().map(|f| display_path(f, source_root)).collect();
+  paths.sort();
+  paths
+}
+
+fn relative_to(path: &Path, root: &Path) -> Option<PathBuf> {
+  if let Ok(stripped) = path.strip_prefix(root) {
+    return Some(stripped.to_path_buf());
+  }
+
+  let path = std::fs::canonicalize(path).ok()?;
+  let root = std::fs::canonicalize(root).ok()?;
+  path.strip_prefix(root).ok().map(Path::to_path_buf)
+}
+
+fn normalize_separators(path: &Path) -> String {
+  let mut result = String::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::RootDir => result.push('/'),
+      Component::Prefix(prefix) => {
+        result.push_str(&prefix.as_os_str().to_string_lossy().replace('\\', "/"))
+      }
+      other => {
+        if !result.is_empty() && !result.ends_with('/') {
+          result.push('/');
+        }
+        result.push_str(&other.as_os_str().to_string_lossy());
+      }
+    }
+  }
+
+  if result.is_empty() {
+    result.push('.');
+  }
+  result
+}
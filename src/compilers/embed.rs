@@ -1,11 +1,13 @@
-use anyhow::{Context, Result};
 use std::{
   collections::BTreeMap,
   path::{Path, PathBuf},
 };
 
 #[allow(unused_imports)]
-use askama::{Template};
+use askama::Template;
+
+use super::options::{CodegenOptions, CppStandard};
+use crate::error::{Error, Result};
 
 #[derive(askama::Template)]
 #[template(path = "resources.h", escape = "none")]
@@ -13,7 +15,10 @@ pub(crate) struct HeaderTemplate<'a> {
   pub(crate) files: &'a [String],
   pub(crate) text_getters: &'a Vec<String>,
   pub(crate) binary_getters: &'a Vec<String>,
-  pub(crate) namespace: &'a str,
+  pub(crate) namespace_open: &'a str,
+  pub(crate) namespace_close: &'a str,
+  pub(crate) include_guard: Option<String>,
+  pub(crate) standard: CppStandard,
   pub(crate) filename: &'a str,
 }
 
@@ -26,7 +31,7 @@ impl<'a> std::fmt::Display for HexBytes<'a> {
     }
     for (i, byte) in self.0.iter().enumerate() {
       if i > 0 {
-        write!(f, ",\n")?;
+        writeln!(f, ",")?;
       }
       write!(f, "0x{:02X}", byte)?;
     }
@@ -40,61 +45,65 @@ pub(crate) struct SourceTemplate<'a> {
   pub(crate) files: &'a [String],
   pub(crate) text_data: &'a BTreeMap<String, String>,
   pub(crate) binary_data: &'a BTreeMap<String, (usize, HexBytes<'a>)>,
-  pub(crate) namespace: &'a str,
+  pub(crate) namespace_open: &'a str,
+  pub(crate) namespace_close: &'a str,
+  pub(crate) standard: CppStandard,
   pub(crate) filename: &'a str,
 }
 
+/// Embeds text and binary files into a C++ header/source pair with one getter per file.
 pub struct EmbedCompiler {
   pub namespace: String,
-  pub out_dir: PathBuf
+  pub out_dir: PathBuf,
+  pub options: CodegenOptions,
 }
 
 impl EmbedCompiler {
   pub fn new(namespace: String, out_dir: &Path) -> Self {
     Self {
       namespace,
-      out_dir: out_dir.to_path_buf()
+      out_dir: out_dir.to_path_buf(),
+      options: CodegenOptions::default(),
     }
   }
 
+  pub fn with_options(mut self, options: CodegenOptions) -> Self {
+    self.options = options;
+    self
+  }
+
   fn compile_internal(
     &self,
     text_files: &[PathBuf],
     binary_files: &[PathBuf],
     stem: &str,
   ) -> Result<(String, String)> {
+    super::common::check_namespace(&self.namespace)?;
     let text_map = Self::read_text_files(text_files)?;
     let binary_map = Self::read_binary_files(binary_files)?;
 
-    let mut file_strings: Vec<String> = text_files
-      .iter()
-      .map(|f| f.to_string_lossy().to_string())
-      .collect();
-    file_strings.extend(
-      binary_files
-        .iter()
-        .map(|f| f.to_string_lossy().to_string())
-    );
-
-    let text_getters: Vec<String> = text_files
-      .iter()
-      .filter_map(|f| f.file_name())
-      .filter_map(|s| s.to_str())
-      .map(|s| s.to_string().replace(".", "_"))
-      .collect();
+    let source_root = self.options.source_root.as_deref();
+    let mut file_strings = super::common::sorted_display_paths(text_files, source_root);
+    file_strings.extend(super::common::sorted_display_paths(
+      binary_files,
+      source_root,
+    ));
 
-    let binary_getters: Vec<String> = binary_files
-      .iter()
-      .filter_map(|f| f.file_name())
-      .filter_map(|s| s.to_str())
-      .map(|s| s.to_string().replace(".", "_"))
-      .collect();
+    let text_getters: Vec<String> = text_map.keys().cloned().collect();
+    let binary_getters: Vec<String> = binary_map.keys().cloned().collect();
+    let namespace_open = self.options.namespace_open(&self.namespace);
+    let namespace_close = self.options.namespace_close(&self.namespace);
 
     let header_template = HeaderTemplate {
       files: &file_strings,
       text_getters: &text_getters,
       binary_getters: &binary_getters,
-      namespace: &self.namespace,
+      namespace_open: &namespace_open,
+      namespace_close: &namespace_close,
+      include_guard: self
+        .options
+        .guard_macro(&self.namespace, &format!("{}.rc.h", stem)),
+      standard: self.options.standard,
       filename: stem,
     };
 
@@ -107,7 +116,9 @@ impl EmbedCompiler {
       files: &file_strings,
       text_data: &text_map,
       binary_data: &binary_data_wrapped,
-      namespace: &self.namespace,
+      namespace_open: &namespace_open,
+      namespace_close: &namespace_close,
+      standard: self.options.standard,
       filename: stem,
     };
 
@@ -117,12 +128,15 @@ impl EmbedCompiler {
     Ok((header_content, source_content))
   }
 
+  /// Writes `<stem>.rc.h` and `<stem>.rc.cc`, returning their paths.
+  ///
+  /// The stem is `output_name`, or the input file stems joined by `_`.
   pub fn compile(
     &self,
     text_files: &[PathBuf],
     binary_files: &[PathBuf],
     output_name: &Option<String>,
-  ) -> Result<PathBuf> {
+  ) -> Result<(PathBuf, PathBuf)> {
     let mut files = text_files.to_vec();
     files.extend(binary_files.to_vec());
     let stem = super::common::output_stem(&files, output_name)?;
@@ -134,26 +148,19 @@ impl EmbedCompiler {
     let out_source_filename = format!("{}.rc.cc", stem);
     let out_source_path = self.out_dir.join(out_source_filename);
 
-    std::fs::create_dir_all(&self.out_dir)?;
-    std::fs::write(out_header_path.clone(), content.0)?;
-    std::fs::write(out_source_path.clone(), content.1)?;
+    std::fs::create_dir_all(&self.out_dir).map_err(Error::io(&self.out_dir))?;
+    std::fs::write(&out_header_path, content.0).map_err(Error::io(&out_header_path))?;
+    std::fs::write(&out_source_path, content.1).map_err(Error::io(&out_source_path))?;
 
-    crate::cli::log_compiled_file(&out_header_path, "Resource");
-    Ok(out_header_path)
+    Ok((out_header_path, out_source_path))
   }
 
   fn read_text_files(files: &[PathBuf]) -> Result<BTreeMap<String, String>> {
     let mut data = BTreeMap::new();
 
     for file in files {
-      let content = std::fs::read_to_string(file)?;
-
-      let stem = file
-        .file_name()
-        .context("Failed to get file stem")?
-        .to_str()
-        .context("Failed to convert stem to string")?;
-      data.insert(stem.to_string().replace(".", "_"), content);
+      let content = std::fs::read_to_string(file).map_err(Error::io(file))?;
+      data.insert(super::common::identifier_name(file)?, content);
     }
     Ok(data)
   }
@@ -162,14 +169,8 @@ impl EmbedCompiler {
     let mut data = BTreeMap::new();
 
     for file in files {
-      let content = std::fs::read(file)?;
-
-      let stem = file
-        .file_name()
-        .context("Failed to get file stem")?
-        .to_str()
-        .context("Failed to convert stem to string")?;
-      data.insert(stem.to_string().replace(".", "_"), content);
+      let content = std::fs::read(file).map_err(Error::io(file))?;
+      data.insert(super::common::identifier_name(file)?, content);
     }
     Ok(data)
   }
@@ -1,26 +1,33 @@
-use anyhow::{Context, Result};
 use std::{
   collections::BTreeMap,
   path::{Path, PathBuf},
 };
 
 #[allow(unused_imports)]
-use askama::{filters, Template};
+use askama::{Template, filters};
+
+use super::options::{CodegenOptions, CppStandard};
+use crate::error::{Error, Result};
 
 #[derive(askama::Template)]
 #[template(path = "json_header.h", escape = "none")]
 pub(crate) struct HeaderTemplate<'a> {
   pub(crate) files: &'a [String],
   pub(crate) json_data: &'a BTreeMap<String, String>,
-  pub(crate) namespace: &'a str,
+  pub(crate) namespace_open: &'a str,
+  pub(crate) namespace_close: &'a str,
+  pub(crate) include_guard: Option<String>,
+  pub(crate) standard: CppStandard,
   pub(crate) output_stem: &'a str,
   pub(crate) with_nlohmann: bool,
 }
 
+/// Embeds JSON files into a C++ header as string literals.
 pub struct JsonCompiler {
   pub namespace: String,
   pub out_dir: PathBuf,
   pub with_nlohmann: bool,
+  pub options: CodegenOptions,
 }
 
 impl JsonCompiler {
@@ -29,62 +36,71 @@ impl JsonCompiler {
       namespace,
       out_dir: out_dir.to_path_buf(),
       with_nlohmann,
+      options: CodegenOptions::default(),
     }
   }
 
-  fn compile_internal(
-    &self,
-    files: &[PathBuf],
-    stem: &str,
-  ) -> Result<String> {
+  pub fn with_options(mut self, options: CodegenOptions) -> Self {
+    self.options = options;
+    self
+  }
+
+  fn compile_internal(&self, files: &[PathBuf], stem: &str) -> Result<String> {
+    super::common::check_namespace(&self.namespace)?;
     let jsons = Self::read_json_files(files)?;
 
-    let file_strings: Vec<String> = files
-      .iter()
-      .map(|f| f.to_string_lossy().to_string())
-      .collect();
+    let file_strings =
+      super::common::sorted_display_paths(files, self.options.source_root.as_deref());
+    let namespace_open = self.options.namespace_open(&self.namespace);
+    let namespace_close = self.options.namespace_close(&self.namespace);
 
     let template = HeaderTemplate {
       files: &file_strings,
       json_data: &jsons,
-      namespace: &self.namespace,
+      namespace_open: &namespace_open,
+      namespace_close: &namespace_close,
+      include_guard: self
+        .options
+        .guard_macro(&self.namespace, &Self::output_filename(stem)),
+      standard: self.options.standard,
       output_stem: stem,
       with_nlohmann: self.with_nlohmann,
     };
     Ok(template.render()?)
   }
 
-  pub fn compile(
-    &self,
-    files: &[PathBuf],
-    output_name: &Option<String>,
-  ) -> Result<PathBuf> {
+  /// Writes `<stem>.json.h` with every file in `files`, returning its path.
+  ///
+  /// The stem is `output_name`, or the input file stems joined by `_`.
+  pub fn compile(&self, files: &[PathBuf], output_name: &Option<String>) -> Result<PathBuf> {
     let stem = super::common::output_stem(files, output_name)?;
     let content = self.compile_internal(files, &stem)?;
 
-    let out_filename = format!("{}.json.h", stem);
-    let out_path = self.out_dir.join(out_filename);
+    let out_path = self.out_dir.join(Self::output_filename(&stem));
 
-    std::fs::create_dir_all(&self.out_dir)?;
-    std::fs::write(out_path.clone(), content)?;
+    std::fs::create_dir_all(&self.out_dir).map_err(Error::io(&self.out_dir))?;
+    std::fs::write(&out_path, content).map_err(Error::io(&out_path))?;
 
-    crate::cli::log_compiled_file(&out_path, "JSON");
     Ok(out_path)
   }
 
+  fn output_filename(stem: &str) -> String {
+    format!("{}.json.h", stem)
+  }
+
   fn read_json_files(files: &[PathBuf]) -> Result<BTreeMap<String, String>> {
     let mut json_data = BTreeMap::new();
 
     for file in files {
-      let content = std::fs::read_to_string(file)?;
-      let _parsed: serde_json::Value = serde_json::from_str(&content)?;
-
-      let stem = file
-        .file_stem()
-        .context("Failed to get file stem")?
-        .to_str()
-        .context("Failed to convert stem to string")?;
-      json_data.insert(stem.to_string(), content);
+      let content = std::fs::read_to_string(file).map_err(Error::io(file))?;
+      let _parsed: serde_json::Value =
+        serde_json::from_str(&content).map_err(|source| Error::Json {
+          path: file.clone(),
+          source,
+        })?;
+
+      let stem = super::common::identifier_stem(file)?;
+      json_data.insert(stem, content);
     }
     Ok(json_data)
   }
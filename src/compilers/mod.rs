@@ -1,4 +1,11 @@
+//! C++ code generators.
+//!
+//! Every compiler renders its inputs into header (and source) files inside an
+//! output directory; shared settings live in [`options::CodegenOptions`].
+
+pub(crate) mod common;
+pub mod embed;
 pub mod json;
+pub mod options;
+pub(crate) mod verify;
 pub mod yaml;
-pub mod embed;
-pub(crate) mod common;
\ No newline at end of file
@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+/// C++ language standard targeted by generated sources.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum CppStandard {
+  #[value(name = "c++11")]
+  Cpp11,
+  #[value(name = "c++14")]
+  Cpp14,
+  #[value(name = "c++17")]
+  Cpp17,
+  #[default]
+  #[value(name = "c++20")]
+  Cpp20,
+  #[value(name = "c++23")]
+  Cpp23,
+}
+
+impl CppStandard {
+  /// Value of the compiler `-std=` flag for this standard.
+  pub fn flag(&self) -> &'static str {
+    match self {
+      CppStandard::Cpp11 => "c++11",
+      CppStandard::Cpp14 => "c++14",
+      CppStandard::Cpp17 => "c++17",
+      CppStandard::Cpp20 => "c++20",
+      CppStandard::Cpp23 => "c++23",
+    }
+  }
+
+  /// `[[nodiscard]]` and `[[maybe_unused]]` attributes.
+  pub fn has_attributes(&self) -> bool {
+    *self >= CppStandard::Cpp17
+  }
+
+  pub fn has_inline_variables(&self) -> bool {
+    *self >= CppStandard::Cpp17
+  }
+
+  /// `namespace a::b {}` definitions.
+  pub fn has_nested_namespaces(&self) -> bool {
+    *self >= CppStandard::Cpp17
+  }
+
+  pub fn has_string_view(&self) -> bool {
+    *self >= CppStandard::Cpp17
+  }
+
+  pub fn has_byte(&self) -> bool {
+    *self >= CppStandard::Cpp17
+  }
+
+  pub fn has_span(&self) -> bool {
+    *self >= CppStandard::Cpp20
+  }
+
+  /// Type returned by getters of embedded text files.
+  pub fn string_type(&self) -> &'static str {
+    if self.has_string_view() {
+      "std::string_view"
+    } else {
+      "char const*"
+    }
+  }
+
+  /// Element type of embedded binary files.
+  pub fn byte_type(&self) -> &'static str {
+    if self.has_byte() {
+      "std::byte"
+    } else {
+      "unsigned char"
+    }
+  }
+
+  /// Type returned by getters of embedded binary files.
+  ///
+  /// Below C++20 this is a minimal span-like struct emitted by the resources header.
+  pub fn bytes_type(&self) -> String {
+    if self.has_span() {
+      format!("std::span<{} const>", self.byte_type())
+    } else {
+      format!("::wucc::span_view<{}>", self.byte_type())
+    }
+  }
+}
+
+/// How generated headers protect themselves from multiple inclusion.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IncludeGuard {
+  /// `#pragma once`.
+  #[default]
+  Pragma,
+  /// Classic `#ifndef`/`#define`/`#endif` guards.
+  Ifndef,
+}
+
+/// Settings shared by all C++ code generators.
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+  /// Source paths listed in generated files are made relative to this directory.
+  pub source_root: Option<PathBuf>,
+  pub standard: CppStandard,
+  pub include_guard: IncludeGuard,
+}
+
+impl CodegenOptions {
+  /// Opening line(s) of `namespace`, spelled for the targeted standard.
+  pub fn namespace_open(&self, namespace: &str) -> String {
+    if self.standard.has_nested_namespaces() {
+      return format!("namespace {} {{", namespace);
+    }
+    namespace
+      .split("::")
+      .map(|part| format!("namespace {} {{", part))
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
+  /// Closing line of `namespace`, matching [`CodegenOptions::namespace_open`].
+  pub fn namespace_close(&self, namespace: &str) -> String {
+    let depth = if self.standard.has_nested_namespaces() {
+      1
+    } else {
+      namespace.split("::").count()
+    };
+    format!("{} // namespace {}", vec!["}"; depth].join(" "), namespace)
+  }
+
+  /// Include guard macro for the header `file_name`, or `None` for `#pragma once`.
+  pub fn guard_macro(&self, namespace: &str, file_name: &str) -> Option<String> {
+    if self.include_guard == IncludeGuard::Pragma {
+      return None;
+    }
+
+    let mut guard = String::new();
+    for c in format!("{}_{}", namespace, file_name).chars() {
+      if c.is_ascii_alphanumeric() {
+        guard.push(c.to_ascii_uppercase());
+      } else if !guard.is_empty() && !guard.ends_with('_') {
+        guard.push('_');
+      }
+    }
+    Some(guard.trim_end_matches('_').to_string())
+  }
+}
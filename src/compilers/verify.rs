@@ -0,0 +1,147 @@
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+use super::options::CppStandard;
+use crate::error::{Error, Result};
+
+/// Compilers probed when `CXX` is not set.
+const COMPILER_CANDIDATES: [&str; 3] = ["c++", "g++", "clang++"];
+
+/// Outcome of [`verify`].
+#[derive(Debug)]
+pub struct Report {
+  /// Compiler command the files were checked with; `None` when no compiler
+  /// was found and verification was skipped.
+  pub compiler: Option<String>,
+  /// Every checked file, with the compiler diagnostics if it failed.
+  pub files: Vec<(PathBuf, Option<String>)>,
+}
+
+impl Report {
+  /// `Ok` when every file compiled, or [`Error::Verification`] naming those
+  /// that did not.
+  pub fn into_result(self) -> Result<()> {
+    let failures: Vec<String> = self
+      .files
+      .iter()
+      .filter(|(_, diagnostics)| diagnostics.is_some())
+      .map(|(file, _)| file.display().to_string())
+      .collect();
+    match self.compiler {
+      Some(compiler) if !failures.is_empty() => Err(Error::Verification {
+        compiler,
+        files: failures,
+      }),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// Checks that every generated file compiles on its own.
+///
+/// Each header is included from an otherwise empty translation unit and each
+/// source is compiled as is, with `-fsyntax-only` and the targeted standard.
+/// The compiler is taken from `CXX`, which may carry arguments of its own like
+/// `ccache g++` (extra flags from `CXXFLAGS`); without it the first available
+/// of `c++`, `g++` and `clang++` is used, and verification is skipped when
+/// none is installed.
+pub fn verify(files: &[PathBuf], standard: CppStandard) -> Result<Report> {
+  let Some(compiler) = find_compiler() else {
+    return Ok(Report {
+      compiler: None,
+      files: Vec::new(),
+    });
+  };
+  let extra_flags: Vec<String> = std::env::var("CXXFLAGS")
+    .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+    .unwrap_or_default();
+
+  let mut checked = Vec::new();
+  for file in files {
+    let output = check_file(&compiler, &extra_flags, file, standard)?;
+    let diagnostics =
+      (!output.status.success()).then(|| String::from_utf8_lossy(&output.stderr).into_owned());
+    checked.push((file.clone(), diagnostics));
+  }
+
+  Ok(Report {
+    compiler: Some(compiler.join(" ")),
+    files: checked,
+  })
+}
+
+/// The compiler command: `CXX` split into the program and its arguments, or
+/// the first candidate that runs.
+fn find_compiler() -> Option<Vec<String>> {
+  if let Ok(cxx) = std::env::var("CXX")
+    && !cxx.trim().is_empty()
+  {
+    return Some(cxx.split_whitespace().map(str::to_string).collect());
+  }
+
+  COMPILER_CANDIDATES
+    .iter()
+    .find(|candidate| {
+      Command::new(candidate)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+    })
+    .map(|candidate| vec![candidate.to_string()])
+}
+
+fn is_header(file: &Path) -> bool {
+  matches!(
+    file.extension().and_then(|e| e.to_str()),
+    Some("h" | "hh" | "hpp" | "hxx")
+  )
+}
+
+fn check_file(
+  compiler: &[String],
+  extra_flags: &[String],
+  file: &Path,
+  standard: CppStandard,
+) -> Result<std::process::Output> {
+  let (program, arguments) = compiler
+    .split_first()
+    .expect("a compiler command is not empty");
+  let mut command = Command::new(program);
+  command
+    .args(arguments)
+    .arg(format!("-std={}", standard.flag()))
+    .arg("-fsyntax-only")
+    .args(extra_flags)
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped());
+
+  let compiler_path = Path::new(program);
+  if !is_header(file) {
+    return command.arg(file).output().map_err(Error::io(compiler_path));
+  }
+
+  // Headers are checked through a translation unit that includes nothing else,
+  // so any include the header forgot to pull in by itself becomes an error.
+  let absolute = std::fs::canonicalize(file).map_err(Error::io(file))?;
+  let mut child = command
+    .args(["-x", "c++", "-"])
+    .stdin(Stdio::piped())
+    .spawn()
+    .map_err(Error::io(compiler_path))?;
+  if let Some(mut stdin) = child.stdin.take() {
+    // A compiler that exits without reading its input closes the pipe early;
+    // its exit status reports the outcome then.
+    match stdin.write_all(format!("#include \"{}\"\n", absolute.display()).as_bytes()) {
+      Err(error) if error.kind() != std::io::ErrorKind::BrokenPipe => {
+        return Err(Error::io(compiler_path)(error));
+      }
+      _ => {}
+    }
+  }
+  child.wait_with_output().map_err(Error::io(compiler_path))
+}
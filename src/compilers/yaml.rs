@@ -1,14 +1,18 @@
-use anyhow::{Context, Result};
+use askama::Template;
 use std::{
   collections::BTreeMap,
   path::{Path, PathBuf},
 };
-use askama::Template;
 
+use super::options::CodegenOptions;
+use crate::error::{Error, Result};
+
+/// Embeds YAML files into a C++ header as JSON string literals.
 pub struct YamlCompiler {
   pub namespace: String,
   pub out_dir: PathBuf,
   pub with_nlohmann: bool,
+  pub options: CodegenOptions,
 }
 
 impl YamlCompiler {
@@ -17,63 +21,75 @@ impl YamlCompiler {
       namespace,
       out_dir: out_dir.to_path_buf(),
       with_nlohmann,
+      options: CodegenOptions::default(),
     }
   }
 
-  fn compile_internal(
-    &self,
-    files: &[PathBuf],
-    stem: &str,
-  ) -> Result<String> {
+  pub fn with_options(mut self, options: CodegenOptions) -> Self {
+    self.options = options;
+    self
+  }
+
+  fn compile_internal(&self, files: &[PathBuf], stem: &str) -> Result<String> {
+    super::common::check_namespace(&self.namespace)?;
     let jsons = Self::read_yaml_files(files)?;
 
-    let file_strings: Vec<String> = files
-      .iter()
-      .map(|f| f.to_string_lossy().to_string())
-      .collect();
+    let file_strings =
+      super::common::sorted_display_paths(files, self.options.source_root.as_deref());
+    let namespace_open = self.options.namespace_open(&self.namespace);
+    let namespace_close = self.options.namespace_close(&self.namespace);
 
     let template = super::json::HeaderTemplate {
       files: &file_strings,
       json_data: &jsons,
-      namespace: &self.namespace,
+      namespace_open: &namespace_open,
+      namespace_close: &namespace_close,
+      include_guard: self
+        .options
+        .guard_macro(&self.namespace, &Self::output_filename(stem)),
+      standard: self.options.standard,
       output_stem: stem,
       with_nlohmann: self.with_nlohmann,
     };
     Ok(template.render()?)
   }
 
-  pub fn compile(
-    &self,
-    files: &[PathBuf],
-    output_name: &Option<String>,
-  ) -> Result<PathBuf> {
+  /// Writes `<stem>.yml.h` with every file in `files`, returning its path.
+  ///
+  /// The stem is `output_name`, or the input file stems joined by `_`.
+  pub fn compile(&self, files: &[PathBuf], output_name: &Option<String>) -> Result<PathBuf> {
     let stem = super::common::output_stem(files, output_name)?;
     let content = self.compile_internal(files, &stem)?;
 
-    let out_filename = format!("{}.yml.h", stem);
-    let out_path = self.out_dir.join(out_filename);
+    let out_path = self.out_dir.join(Self::output_filename(&stem));
 
-    std::fs::create_dir_all(&self.out_dir)?;
-    std::fs::write(out_path.clone(), content)?;
+    std::fs::create_dir_all(&self.out_dir).map_err(Error::io(&self.out_dir))?;
+    std::fs::write(&out_path, content).map_err(Error::io(&out_path))?;
 
-    crate::cli::log_compiled_file(&out_path, "YAML");
     Ok(out_path)
   }
 
+  fn output_filename(stem: &str) -> String {
+    format!("{}.yml.h", stem)
+  }
+
   fn read_yaml_files(files: &[PathBuf]) -> Result<BTreeMap<String, String>> {
     let mut json_data = BTreeMap::new();
 
     for file in files {
-      let content = std::fs::read_to_string(file)?;
-      let yaml: serde_norway::Value = serde_norway::from_str(&content)?;
-      let json = serde_json::to_string(&yaml)?;
+      let content = std::fs::read_to_string(file).map_err(Error::io(file))?;
+      let yaml: serde_norway::Value =
+        serde_norway::from_str(&content).map_err(|source| Error::Yaml {
+          path: file.clone(),
+          source,
+        })?;
+      let json = serde_json::to_string(&yaml).map_err(|source| Error::Json {
+        path: file.clone(),
+        source,
+      })?;
 
-      let stem = file
-        .file_stem()
-        .context("Failed to get file stem")?
-        .to_str()
-        .context("Failed to convert stem to string")?;
-      json_data.insert(stem.to_string(), json);
+      let stem = super::common::identifier_stem(file)?;
+      json_data.insert(stem, json);
     }
     Ok(json_data)
   }
@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+
+/// Errors returned by the code generators, version handlers and hexdump utilities.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  /// Reading or writing a file failed.
+  #[error("failed to access {}", path.display())]
+  Io {
+    path: PathBuf,
+    #[source]
+    source: std::io::Error,
+  },
+
+  /// An input file is not valid JSON.
+  #[error("failed to parse JSON file {}", path.display())]
+  Json {
+    path: PathBuf,
+    #[source]
+    source: serde_json::Error,
+  },
+
+  /// An input file is not valid YAML.
+  #[error("failed to parse YAML file {}", path.display())]
+  Yaml {
+    path: PathBuf,
+    #[source]
+    source: serde_norway::Error,
+  },
+
+  /// A version file is not valid TOML.
+  #[error("failed to parse TOML file {}", path.display())]
+  Toml {
+    path: PathBuf,
+    #[source]
+    source: toml_edit::TomlError,
+  },
+
+  /// The version configuration (`wucc.yml`, `--file`) is not usable.
+  #[error("invalid version configuration: {0}")]
+  Config(String),
+
+  /// A file name cannot be turned into a stem or identifier.
+  #[error("invalid file name: {}", .0.display())]
+  InvalidFileName(PathBuf),
+
+  /// Rendering a code template failed.
+  #[error("failed to render template")]
+  Template(#[from] askama::Error),
+
+  /// Generated files did not compile with the local C++ compiler.
+  #[error("{} generated file(s) failed to compile with {compiler}: {}", files.len(), files.join(", "))]
+  Verification {
+    compiler: String,
+    files: Vec<String>,
+  },
+
+  /// A version string is not valid semver.
+  #[error("invalid version '{version}'")]
+  InvalidVersion {
+    version: String,
+    #[source]
+    source: semver::Error,
+  },
+
+  /// A version is too large for the field it has to fit.
+  #[error("version {version} does not fit {field}")]
+  VersionOutOfRange {
+    version: semver::Version,
+    field: String,
+  },
+
+  /// A version file or a version field inside of it does not exist.
+  #[error("{0}")]
+  NotFound(String),
+
+  /// A pattern used to locate versions failed to compile.
+  #[error("invalid pattern")]
+  Regex(#[from] regex::Error),
+
+  /// Dumping or rendering binary data failed.
+  #[error("hexdump failed: {0}")]
+  Hexdump(String),
+
+  /// A name taken from the inputs cannot be used as a C++ identifier.
+  #[error("'{identifier}' from {origin} is not a valid C++ identifier")]
+  InvalidIdentifier { identifier: String, origin: String },
+
+  /// Several macro prefixes in an export header are equally likely to hold the version.
+  #[error(
+    "ambiguous version macro prefix in {}: {}",
+    path.display(),
+    candidates.join(", ")
+  )]
+  AmbiguousPrefix {
+    path: PathBuf,
+    candidates: Vec<String>,
+  },
+
+  /// Some version files could not be updated; the others were written.
+  #[error(
+    "failed to update {} version file(s): {}",
+    failures.len(),
+    failures.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+  )]
+  VersionWrite { failures: Vec<(String, Error)> },
+
+  /// Version files disagree with the reference version.
+  #[error(
+    "{} version file(s) out of sync with {reference} ({version}): {}",
+    files.len(),
+    files.join(", ")
+  )]
+  VersionMismatch {
+    reference: String,
+    version: semver::Version,
+    files: Vec<String>,
+  },
+
+  /// A git command failed.
+  #[error("git {command} failed: {message}")]
+  Git { command: String, message: String },
+
+  /// The working tree has uncommitted changes to tracked files.
+  #[error(
+    "working tree has uncommitted changes ({}); commit them or pass --force",
+    files.join(", ")
+  )]
+  DirtyWorkingTree { files: Vec<String> },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+  /// Process exit code for this class of error, as listed under
+  /// [Exit codes](https://github.com/whs31/wucc#exit-codes) in the README.
+  ///
+  /// Code 2 is left to command line usage errors. Failed version writes report
+  /// the code shared by all of their failures, or 1 when they differ.
+  pub fn exit_code(&self) -> u8 {
+    match self {
+      Error::Io { .. } => 3,
+      Error::Json { .. }
+      | Error::Yaml { .. }
+      | Error::Toml { .. }
+      | Error::Config(_)
+      | Error::InvalidVersion { .. }
+      | Error::VersionOutOfRange { .. } => 4,
+      Error::NotFound(_) => 5,
+      Error::AmbiguousPrefix { .. } => 6,
+      Error::InvalidFileName(_) | Error::InvalidIdentifier { .. } => 7,
+      Error::Verification { .. } => 8,
+      Error::VersionMismatch { .. } => 9,
+      Error::Git { .. } => 10,
+      Error::DirtyWorkingTree { .. } => 11,
+      Error::VersionWrite { failures } => {
+        let mut codes = failures.iter().map(|(_, error)| error.exit_code());
+        let first = codes.next().unwrap_or(1);
+        if codes.all(|code| code == first) {
+          first
+        } else {
+          1
+        }
+      }
+      Error::Template(_) | Error::Regex(_) | Error::Hexdump(_) => 1,
+    }
+  }
+
+  /// Returns a closure wrapping an I/O error with the `path` it happened on,
+  /// for use with [`Result::map_err`].
+  pub(crate) fn io(path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+    move |source| Error::Io {
+      path: path.to_path_buf(),
+      source,
+    }
+  }
+}
@@ -0,0 +1,172 @@
+//! Minimal git support, driven through the `git` binary.
+
+use crate::error::{Error, Result};
+use std::{
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+/// A commit, as listed by [`Repository::commits`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+  pub hash: String,
+  pub message: String,
+}
+
+/// A git working tree, addressed from a directory inside of it.
+pub struct Repository {
+  dir: PathBuf,
+}
+
+impl Repository {
+  /// Opens the repository containing `dir`.
+  pub fn discover(dir: &Path) -> Result<Self> {
+    let repository = Self {
+      dir: dir.to_path_buf(),
+    };
+    repository.run(&["rev-parse", "--show-toplevel"])?;
+    Ok(repository)
+  }
+
+  /// Tracked files with uncommitted changes, staged or not.
+  pub fn changed_files(&self) -> Result<Vec<String>> {
+    let status = self.run(&["status", "--porcelain", "--untracked-files=no"])?;
+    Ok(
+      status
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(str::to_string)
+        .collect(),
+    )
+  }
+
+  /// Whether the tag `name` exists.
+  pub fn tag_exists(&self, name: &str) -> Result<bool> {
+    let reference = format!("refs/tags/{}", name);
+    let output = self.output(&["rev-parse", "--quiet", "--verify", &reference])?;
+    Ok(output.status.success())
+  }
+
+  /// Stages `files` and commits exactly them, leaving anything else that is
+  /// staged alone. Returns the hash of the new commit.
+  pub fn commit(&self, files: &[PathBuf], message: &str) -> Result<String> {
+    let paths: Vec<&str> = files.iter().filter_map(|file| file.to_str()).collect();
+
+    let mut add = vec!["add", "--"];
+    add.extend(&paths);
+    self.run(&add)?;
+
+    let mut commit = vec!["commit", "--quiet", "-m", message, "--"];
+    commit.extend(&paths);
+    self.run(&commit)?;
+
+    self.head()
+  }
+
+  /// Hash of the commit checked out.
+  pub fn head(&self) -> Result<String> {
+    Ok(self.run(&["rev-parse", "HEAD"])?.trim().to_string())
+  }
+
+  /// Creates the annotated tag `name` on `HEAD`.
+  pub fn tag(&self, name: &str, message: &str) -> Result<()> {
+    self.run(&["tag", "-a", name, "-m", message])?;
+    Ok(())
+  }
+
+  /// `<prefix><version>` tags reachable from `HEAD` with their versions,
+  /// lowest first.
+  pub fn version_tags(&self, prefix: &str) -> Result<Vec<(String, semver::Version)>> {
+    let pattern = format!("{}*", prefix);
+    let tags = self.run(&["tag", "--list", &pattern, "--merged", "HEAD"])?;
+    let mut tags: Vec<(String, semver::Version)> = tags
+      .lines()
+      .filter_map(|tag| {
+        let version = semver::Version::parse(tag.strip_prefix(prefix)?).ok()?;
+        Some((tag.to_string(), version))
+      })
+      .collect();
+    tags.sort_by(|(_, a), (_, b)| a.cmp(b));
+    Ok(tags)
+  }
+
+  /// Highest `<prefix><version>` tag reachable from `HEAD`, with its version.
+  pub fn latest_version_tag(&self, prefix: &str) -> Result<Option<(String, semver::Version)>> {
+    Ok(self.version_tags(prefix)?.pop())
+  }
+
+  /// Commits after `since` up to `until`, newest first; all commits of
+  /// `until` without `since`.
+  pub fn commits(&self, since: Option<&str>, until: &str) -> Result<Vec<Commit>> {
+    self.commits_touching(since, until, None)
+  }
+
+  /// Like [`Repository::commits`], limited to the commits that change `path`
+  /// when given.
+  pub fn commits_touching(
+    &self,
+    since: Option<&str>,
+    until: &str,
+    path: Option<&Path>,
+  ) -> Result<Vec<Commit>> {
+    let range = since.map_or_else(
+      || until.to_string(),
+      |since| format!("{}..{}", since, until),
+    );
+    let mut args = vec!["log", "--format=%H%x1f%B%x1e", &range];
+    if let Some(path) = path.and_then(Path::to_str) {
+      args.extend(["--", path]);
+    }
+    let log = self.run(&args)?;
+    Ok(
+      log
+        .split('\x1e')
+        .filter_map(|entry| {
+          let (hash, message) = entry.trim().split_once('\x1f')?;
+          Some(Commit {
+            hash: hash.to_string(),
+            message: message.trim().to_string(),
+          })
+        })
+        .collect(),
+    )
+  }
+
+  /// Full messages of the commits after `since` up to `HEAD` that change
+  /// `path` when given, newest first; all commits of `HEAD` without `since`.
+  pub fn commit_messages(&self, since: Option<&str>, path: Option<&Path>) -> Result<Vec<String>> {
+    Ok(
+      self
+        .commits_touching(since, "HEAD", path)?
+        .into_iter()
+        .map(|commit| commit.message)
+        .collect(),
+    )
+  }
+
+  /// Committer date of `revision`, as `YYYY-MM-DD`.
+  pub fn date(&self, revision: &str) -> Result<String> {
+    let date = self.run(&["log", "-1", "--format=%cs", revision])?;
+    Ok(date.trim().to_string())
+  }
+
+  fn output(&self, args: &[&str]) -> Result<Output> {
+    Command::new("git")
+      .current_dir(&self.dir)
+      .args(args)
+      .output()
+      .map_err(Error::io(Path::new("git")))
+  }
+
+  /// Runs git with `args`, returning its stdout or failing with its stderr.
+  fn run(&self, args: &[&str]) -> Result<String> {
+    let output = self.output(args)?;
+    if !output.status.success() {
+      return Err(Error::Git {
+        command: args.join(" "),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+      });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+  }
+}
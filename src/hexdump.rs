@@ -1,9 +1,13 @@
-use crate::args::{HexdumpFormat, HexdumpGenerateFormat};
-use anyhow::Result;
-use std::io::{Read, Write};
+//! `xxd`-like hexdumps and source code generation from binary data.
+
+use crate::args::HexdumpFormat;
+use crate::error::{Error, Result};
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
 use xxd::generate::Render;
 
-pub fn run(args: crate::args::HexdumpArgs) -> Result<()> {
+/// Runs the `hexdump` subcommand.
+pub(crate) fn run(args: crate::args::HexdumpArgs) -> Result<()> {
   match args.subcommand {
     crate::args::HexdumpSubcommand::Dump(a) => {
       dump(args.output_file, args.start_offset, args.length, &a)
@@ -14,27 +18,26 @@ pub fn run(args: crate::args::HexdumpArgs) -> Result<()> {
   }
 }
 
-fn create_reader(path: String) -> Result<Box<dyn Read>> {
-  match path.as_ref() {
-    "stdin" => Ok(Box::new(std::io::stdin())),
-    _ => {
-      let file_reader = std::fs::File::open(path)?;
-      Ok(Box::new(file_reader))
-    }
-  }
+fn create_reader(path: String) -> Result<BufReader<Box<dyn Read>>> {
+  let reader: Box<dyn Read> = match path.as_ref() {
+    "stdin" => Box::new(std::io::stdin()),
+    _ => Box::new(std::fs::File::open(&path).map_err(Error::io(Path::new(&path)))?),
+  };
+  Ok(BufReader::new(reader))
 }
 
 fn create_writer(path: String) -> Result<Box<dyn Write>> {
   match path.as_ref() {
     "stdout" => Ok(Box::new(std::io::stdout())),
     _ => {
-      let file_writer = std::fs::File::create(path)?;
+      let file_writer = std::fs::File::create(&path).map_err(Error::io(Path::new(&path)))?;
       Ok(Box::new(file_writer))
     }
   }
 }
 
-pub fn dump(
+/// Writes a hexdump of `args.file` (or stdin) to `output_file` (or stdout).
+pub(crate) fn dump(
   output_file: Option<String>,
   start_offset: u64,
   length: Option<u64>,
@@ -54,9 +57,11 @@ pub fn dump(
       settings,
     ),
   }
+  .map_err(|e| Error::Hexdump(e.to_string()))
 }
 
-pub fn generate(
+/// Writes `args.file` (or stdin) as a source code array to `output_file` (or stdout).
+pub(crate) fn generate(
   output_file: Option<String>,
   start_offset: u64,
   length: Option<u64>,
@@ -67,24 +72,6 @@ pub fn generate(
   let seek = start_offset as usize;
   let reader = create_reader(input_file.clone())?;
   let mut writer = create_writer(output_file.clone())?;
-  let mut template = xxd::generate::Template::new(match args.template {
-    HexdumpGenerateFormat::C => xxd::generate::Language::C,
-    HexdumpGenerateFormat::Cpp => xxd::generate::Language::Cpp,
-    HexdumpGenerateFormat::Rust => xxd::generate::Language::Rust,
-    HexdumpGenerateFormat::Python => xxd::generate::Language::Python,
-  });
-  if let Some(prefix) = &args.prefix {
-    template.set_prefix(prefix.clone());
-  }
-  if let Some(suffix) = &args.suffix {
-    template.set_suffix(suffix.clone());
-  }
-  if let Some(separator) = &args.separator {
-    template.set_separator(separator.clone());
-  }
-  if let Some(bytes_per_line) = args.line_size {
-    template.set_bytes_per_line(bytes_per_line as usize);
-  }
   let data: Vec<u8> = match length {
     None => reader.bytes().skip(seek).flatten().collect(),
     Some(n) => reader
@@ -94,8 +81,53 @@ pub fn generate(
       .flatten()
       .collect(),
   };
-  writer.write_fmt(format_args!("{}\n", template.render(&data)))?;
-  Ok(())
+  writer
+    .write_fmt(format_args!("{}\n", render(&data, &args.options())))
+    .map_err(Error::io(Path::new(&output_file)))
+}
+
+/// Language of the source code array written by [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+  #[default]
+  C,
+  Cpp,
+  Rust,
+  Python,
+}
+
+/// Settings of the source code array written by [`render`]; unset fields
+/// keep the defaults of the language template.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+  pub language: Language,
+  pub prefix: Option<String>,
+  pub suffix: Option<String>,
+  pub separator: Option<String>,
+  pub bytes_per_line: Option<usize>,
+}
+
+/// Renders `data` as a source code array.
+pub fn render(data: &[u8], options: &GenerateOptions) -> String {
+  let mut template = xxd::generate::Template::new(match options.language {
+    Language::C => xxd::generate::Language::C,
+    Language::Cpp => xxd::generate::Language::Cpp,
+    Language::Rust => xxd::generate::Language::Rust,
+    Language::Python => xxd::generate::Language::Python,
+  });
+  if let Some(prefix) = &options.prefix {
+    template.set_prefix(prefix.clone());
+  }
+  if let Some(suffix) = &options.suffix {
+    template.set_suffix(suffix.clone());
+  }
+  if let Some(separator) = &options.separator {
+    template.set_separator(separator.clone());
+  }
+  if let Some(bytes_per_line) = options.bytes_per_line {
+    template.set_bytes_per_line(bytes_per_line);
+  }
+  template.render(data)
 }
 
 fn create_dump_settings(
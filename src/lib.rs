@@ -0,0 +1,49 @@
+//! whs31's utilities and compilers collection.
+//!
+//! `wucc` generates C++ sources from JSON, YAML and arbitrary resource files,
+//! keeps version numbers in sync across build system files and produces
+//! `xxd`-like hexdumps. The `wucc` binary is a thin command line layer over
+//! this crate, so the same code paths are available to `build.rs` scripts:
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//! use wucc::compilers::embed::EmbedCompiler;
+//!
+//! let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//! let (header, source) = EmbedCompiler::new("app::resources".to_string(), &out_dir).compile(
+//!   &[PathBuf::from("assets/shader.glsl")],
+//!   &[PathBuf::from("assets/logo.png")],
+//!   &Some("resources".to_string()),
+//! )?;
+//! # Ok::<(), wucc::Error>(())
+//! ```
+//!
+//! Version files detected in the current directory are accessed through
+//! [`version::VersionIO`]:
+//!
+//! ```no_run
+//! use wucc::version::VersionIO;
+//!
+//! for (name, file) in <dyn VersionIO>::all() {
+//!   println!("{}: {}", name, file.read()?);
+//! }
+//! # Ok::<(), wucc::Error>(())
+//! ```
+
+pub(crate) mod args;
+mod cli;
+pub mod compilers;
+mod error;
+pub mod git;
+pub mod hexdump;
+mod misc;
+pub mod version;
+
+pub use self::error::{Error, Result};
+
+/// Parses the command line and runs it, returning the exit code of the
+/// `wucc` binary.
+#[doc(hidden)]
+pub fn run_cli() -> std::process::ExitCode {
+  cli::run()
+}
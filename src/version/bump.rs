@@ -0,0 +1,77 @@
+use crate::error::{Error, Result};
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// Component of a version to increment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bump {
+  Major,
+  Minor,
+  Patch,
+}
+
+impl std::fmt::Display for Bump {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      Bump::Major => "major",
+      Bump::Minor => "minor",
+      Bump::Patch => "patch",
+    })
+  }
+}
+
+/// Increments `part` of `version`, dropping its pre-release and build metadata.
+pub fn bump(version: &Version, part: Bump) -> Version {
+  match part {
+    Bump::Major => Version::new(version.major + 1, 0, 0),
+    Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+    Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+  }
+}
+
+/// Next `id` pre-release after `version`.
+///
+/// `1.2.0-rc.3` becomes `1.2.0-rc.4` and an earlier pre-release of `1.2.0`,
+/// like `1.2.0-beta.2`, becomes `1.2.0-rc.1`. Going back to `beta` from
+/// `1.2.0-rc.3` is refused, as `1.2.0-beta.1` would come before it. A release
+/// has no pre-releases left, so `1.2.0` becomes `1.2.1-rc.1`.
+pub fn next_prerelease(version: &Version, id: &str) -> Result<Version> {
+  if version.pre.is_empty() {
+    return with_prerelease(&bump(version, Bump::Patch), id, 1);
+  }
+
+  let number = version
+    .pre
+    .as_str()
+    .strip_prefix(id)
+    .and_then(|rest| rest.strip_prefix('.'))
+    .and_then(|number| number.parse::<u64>().ok());
+  let next = with_prerelease(version, id, number.map_or(1, |number| number + 1))?;
+  if next < *version {
+    return Err(Error::Config(format!(
+      "pre-release {} would come before {}; bump the version to start '{}' pre-releases",
+      next, version, id
+    )));
+  }
+  Ok(next)
+}
+
+/// `version` as the `number`-th `id` pre-release, without build metadata.
+pub fn with_prerelease(version: &Version, id: &str, number: u64) -> Result<Version> {
+  let pre = format!("{}.{}", id, number);
+  let mut version = Version::new(version.major, version.minor, version.patch);
+  version.pre = Prerelease::new(&pre).map_err(|source| Error::InvalidVersion {
+    version: pre,
+    source,
+  })?;
+  Ok(version)
+}
+
+/// `version` with `build` as its build metadata.
+pub fn with_build(version: &Version, build: &str) -> Result<Version> {
+  let mut version = version.clone();
+  version.build = BuildMetadata::new(build).map_err(|source| Error::InvalidVersion {
+    version: build.to_string(),
+    source,
+  })?;
+  Ok(version)
+}
@@ -1,98 +1,446 @@
-use anyhow::{Context, Result};
+use super::toml_doc::{get_mut, parse, set_string};
+use crate::error::{Error, Result};
 use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, TableLike};
 
+/// Tables listing the dependencies of a crate, also found under `target.<cfg>`.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Cargo manifest: a crate, a workspace root or a workspace member.
+///
+/// Besides the version itself, the version requirements of path dependencies
+/// on other crates of the workspace are kept in step, so the crates can
+/// still be published after a bump.
 pub struct CargoFile {
   path: PathBuf,
+  /// Root manifest of the workspace, which members may inherit the version from.
+  workspace_root: Option<PathBuf>,
+  /// Names of the crates in the workspace.
+  crates: Vec<String>,
+}
+
+/// `Cargo.lock`, holding the versions of the workspace crates next to those
+/// of their dependencies.
+pub struct CargoLockFile {
+  path: PathBuf,
+  crates: Vec<String>,
+}
+
+/// Crates of a workspace, found from its root manifest.
+pub(crate) struct Workspace {
+  root: PathBuf,
+  /// Crate names and manifests of the members other than the root, sorted
+  /// by manifest.
+  members: Vec<(String, PathBuf)>,
+  crates: Vec<String>,
+  /// Whether the root manifest declares a `[workspace]`.
+  is_workspace: bool,
+}
+
+impl Workspace {
+  /// Reads the workspace rooted at `dir/Cargo.toml`.
+  pub(crate) fn discover_in(dir: &Path) -> Result<Self> {
+    let root = dir.join("Cargo.toml");
+    let content = std::fs::read_to_string(&root).map_err(Error::io(&root))?;
+    let document = parse(&root, &content)?;
+    let base = root.parent().unwrap_or(Path::new(""));
+
+    let strings = |keys: &[&str]| -> Vec<String> {
+      let mut item = document.as_item();
+      for key in keys {
+        match item.get(key) {
+          Some(next) => item = next,
+          None => return Vec::new(),
+        }
+      }
+      item
+        .as_array()
+        .map(|array| {
+          array
+            .iter()
+            .filter_map(|value| value.as_str())
+            .map(str::to_string)
+            .collect()
+        })
+        .unwrap_or_default()
+    };
+    let excluded: Vec<PathBuf> = strings(&["workspace", "exclude"])
+      .iter()
+      .map(|path| base.join(path))
+      .collect();
+
+    let mut manifests: Vec<PathBuf> = strings(&["workspace", "members"])
+      .iter()
+      .flat_map(|pattern| super::glob::expand(base, pattern))
+      .filter(|dir| dir.is_dir() && !excluded.contains(dir) && !same_dir(dir, base))
+      .map(|dir| dir.join("Cargo.toml"))
+      .filter(|manifest| manifest.is_file())
+      .collect();
+    manifests.sort();
+    manifests.dedup();
+
+    let mut crates: Vec<String> = package_name(&document).into_iter().collect();
+    let mut members = Vec::new();
+    for manifest in manifests {
+      let content = std::fs::read_to_string(&manifest).map_err(Error::io(&manifest))?;
+      let name = package_name(&parse(&manifest, &content)?);
+      crates.extend(name.clone());
+      members.push((name.unwrap_or_default(), manifest));
+    }
+
+    Ok(Self {
+      root,
+      members,
+      crates,
+      is_workspace: document.get("workspace").is_some(),
+    })
+  }
+}
+
+impl CargoFile {
+  /// Manifests of the workspace members below the root `Cargo.toml`, with
+  /// their crate names.
+  pub fn members() -> Result<Vec<(String, Self)>> {
+    Self::members_in(Path::new(""))
+  }
+
+  /// Manifests of the workspace members below `dir/Cargo.toml`.
+  pub fn members_in(dir: &Path) -> Result<Vec<(String, Self)>> {
+    Ok(Self::members_of(&Workspace::discover_in(dir)?))
+  }
+
+  /// Manifests of the members of an already discovered `workspace`.
+  pub(crate) fn members_of(workspace: &Workspace) -> Vec<(String, Self)> {
+    workspace
+      .members
+      .iter()
+      .map(|(name, member)| {
+        let file = Self {
+          path: member.clone(),
+          workspace_root: Some(workspace.root.clone()),
+          crates: workspace.crates.clone(),
+        };
+        (name.clone(), file)
+      })
+      .collect()
+  }
+
+  /// Finds `dir/Cargo.toml` as [`VersionIO::new_auto_in`] does, given the
+  /// `workspace` it roots, if that could be read.
+  ///
+  /// [`VersionIO::new_auto_in`]: super::VersionIO::new_auto_in
+  pub(crate) fn in_workspace(dir: &Path, workspace: Option<&Workspace>) -> Result<Self> {
+    use super::VersionIO;
+
+    let path = dir.join("Cargo.toml");
+    if !path.exists() {
+      return Err(Error::NotFound("Cargo.toml not found".to_string()));
+    }
+
+    // A manifest that cannot be parsed is still listed, with its error.
+    let file = Self {
+      path,
+      workspace_root: None,
+      crates: workspace
+        .map(|workspace| workspace.crates.clone())
+        .unwrap_or_default(),
+    };
+    if workspace.is_some_and(|workspace| workspace.is_workspace) && file.read().is_err() {
+      return Err(Error::NotFound(
+        "Cargo.toml is a workspace root without a version".to_string(),
+      ));
+    }
+    Ok(file)
+  }
+
+  fn not_found() -> Error {
+    Error::NotFound("Version field not found in [package] section of Cargo.toml".to_string())
+  }
+
+  fn version_in(path: &Path, document: &DocumentMut) -> Option<Result<semver::Version>> {
+    let version = document
+      .get("package")
+      .and_then(|package| package.get("version"))
+      .or_else(|| {
+        document
+          .get("workspace")
+          .and_then(|workspace| workspace.get("package"))
+          .and_then(|package| package.get("version"))
+      })?;
+    match version.as_str() {
+      Some(version) => Some(super::parse_version(version)),
+      None if inherits_version(version) => None,
+      None => Some(Err(Error::NotFound(format!(
+        "Version of {} is not a string",
+        crate::compilers::common::display_path(path, None)
+      )))),
+    }
+  }
 }
 
 impl super::VersionIO for CargoFile {
   fn new(path: &Path) -> Self {
     Self {
       path: path.to_path_buf(),
+      workspace_root: None,
+      crates: Vec::new(),
     }
   }
 
-  fn new_auto() -> Result<Self> {
-    let path_candidates = vec!["Cargo.toml"];
-    let mut path = None;
-    for path_candidate in path_candidates {
-      let path_candidate = PathBuf::from(path_candidate);
-      if path_candidate.exists() {
-        path = Some(path_candidate);
-        break;
-      }
-    }
-    Ok(Self::new(
-      path.context("Failed to find cargo file")?.as_path(),
-    ))
+  /// Finds `Cargo.toml`, unless it is the root of a workspace without a
+  /// `[workspace.package]` version; its members are then listed on their own.
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    Self::in_workspace(dir, Workspace::discover_in(dir).ok().as_ref())
+  }
+
+  fn kind(&self) -> &'static str {
+    "cargo"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
   }
 
   fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read cargo file: {}", self.path.display()))?;
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    if let Some(version) = Self::version_in(&self.path, &parse(&self.path, &content)?) {
+      return version;
+    }
 
-    let re = regex::Regex::new(r#"(?m)^\s*version\s*=\s*"([^"]+)"\s*$"#)
-      .context("Failed to compile regex")?;
-    let mut in_package_section = false;
+    // `version.workspace = true`: the version comes from the workspace root.
+    let root = self.workspace_root.as_ref().ok_or_else(Self::not_found)?;
+    let content = std::fs::read_to_string(root).map_err(Error::io(root))?;
+    let document = parse(root, &content)?;
+    document
+      .get("workspace")
+      .and_then(|workspace| workspace.get("package"))
+      .and_then(|package| package.get("version"))
+      .and_then(Item::as_str)
+      .ok_or_else(Self::not_found)
+      .and_then(super::parse_version)
+  }
 
-    for line in content.lines() {
-      if line.trim().starts_with('[') {
-        in_package_section = line.trim() == "[package]";
-        continue;
-      }
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let mut document = parse(&self.path, content)?;
+    let new_version = version.to_string();
 
-      if in_package_section {
-        if let Some(captures) = re.captures(line) {
-          let version_str = captures.get(1).unwrap().as_str();
-          return semver::Version::parse(version_str)
-            .with_context(|| format!("Failed to parse version: {}", version_str));
-        }
-      }
+    let mut found = false;
+    if let Some(item) = get_mut(document.as_item_mut(), &["package", "version"]) {
+      found |= set_string(item, &new_version) || inherits_version(item);
+    }
+    if let Some(item) = get_mut(document.as_item_mut(), &["workspace", "package", "version"]) {
+      found |= set_string(item, &new_version);
+    }
+    if !found {
+      return Err(Self::not_found());
     }
 
-    Err(anyhow::anyhow!(
-      "Version field not found in [package] section of Cargo.toml"
-    ))
+    update_requirements(&mut document, &self.crates, version);
+    Ok(document.to_string())
   }
+}
+
+impl CargoLockFile {
+  /// Finds `dir/Cargo.lock` as [`VersionIO::new_auto_in`] does, given the
+  /// `workspace` rooted next to it.
+  ///
+  /// [`VersionIO::new_auto_in`]: super::VersionIO::new_auto_in
+  pub(crate) fn in_workspace(dir: &Path, workspace: &Workspace) -> Result<Self> {
+    use super::VersionIO;
 
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read cargo file: {}", self.path.display()))?;
+    let path = dir.join("Cargo.lock");
+    if !path.exists() {
+      return Err(Error::NotFound("Cargo.lock not found".to_string()));
+    }
 
-    let re = regex::Regex::new(r#"(?m)^(\s*version\s*=\s*")[^"]+(".*)$"#)
-      .context("Failed to compile regex")?;
+    let file = Self {
+      path,
+      crates: workspace.crates.clone(),
+    };
+    file.read()?;
+    Ok(file)
+  }
+}
 
-    let mut in_package_section = false;
-    let mut new_lines = Vec::new();
-    let mut version_replaced = false;
+impl super::VersionIO for CargoLockFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+      crates: Vec::new(),
+    }
+  }
 
-    for line in content.lines() {
-      let current_line = line;
+  /// Finds `Cargo.lock` next to `Cargo.toml` when it lists any of its crates.
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    Self::in_workspace(dir, &Workspace::discover_in(dir)?)
+  }
 
-      if current_line.trim().starts_with('[') {
-        in_package_section = current_line.trim() == "[package]";
-        new_lines.push(current_line.to_string());
-        continue;
+  fn kind(&self) -> &'static str {
+    "cargo-lock"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let document = parse(&self.path, &content)?;
+    let packages = document
+      .get("package")
+      .and_then(Item::as_array_of_tables)
+      .ok_or_else(|| self.not_found())?;
+
+    // The first crate is the root package, if there is one.
+    let version = self
+      .crates
+      .iter()
+      .find_map(|name| {
+        packages
+          .iter()
+          .find(|package| is_local(package, name))?
+          .get("version")?
+          .as_str()
+      })
+      .ok_or_else(|| self.not_found())?;
+    super::parse_version(version)
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let mut document = parse(&self.path, content)?;
+    let packages = document
+      .get_mut("package")
+      .and_then(Item::as_array_of_tables_mut)
+      .ok_or_else(|| self.not_found())?;
+
+    let mut found = false;
+    for package in packages.iter_mut() {
+      if self.crates.iter().any(|name| is_local(package, name))
+        && let Some(item) = package.get_mut("version")
+      {
+        found |= set_string(item, &version.to_string());
       }
+    }
+    if !found {
+      return Err(self.not_found());
+    }
+    Ok(document.to_string())
+  }
+}
+
+impl CargoLockFile {
+  fn not_found(&self) -> Error {
+    Error::NotFound("No workspace crate found in Cargo.lock".to_string())
+  }
+}
+
+fn package_name(document: &DocumentMut) -> Option<String> {
+  document
+    .get("package")?
+    .get("name")?
+    .as_str()
+    .map(str::to_string)
+}
+
+/// Whether `item` is `version.workspace = true`.
+fn inherits_version(item: &Item) -> bool {
+  item
+    .as_table_like()
+    .and_then(|table| table.get("workspace"))
+    .and_then(Item::as_bool)
+    .unwrap_or(false)
+}
 
-      if in_package_section && !version_replaced {
-        if let Some(captures) = re.captures(current_line) {
-          let new_line = format!("{}{}{}", &captures[1], version, &captures[2]);
-          new_lines.push(new_line);
-          version_replaced = true;
-          continue;
+/// Whether the lockfile entry `package` is the crate `name` of the workspace
+/// rather than a dependency from a registry or git.
+fn is_local(package: &toml_edit::Table, name: &str) -> bool {
+  package.get("name").and_then(Item::as_str) == Some(name) && !package.contains_key("source")
+}
+
+/// Points the version requirements of path dependencies on `crates` at `version`.
+fn update_requirements(document: &mut DocumentMut, crates: &[String], version: &semver::Version) {
+  let mut tables: Vec<&mut Item> = Vec::new();
+  let root = document.as_table_mut();
+  for (key, item) in root.iter_mut() {
+    if DEPENDENCY_TABLES.contains(&key.get()) {
+      tables.push(item);
+    } else if key.get() == "workspace" {
+      if let Some(dependencies) = get_mut(item, &["dependencies"]) {
+        tables.push(dependencies);
+      }
+    } else if key.get() == "target"
+      && let Some(targets) = item.as_table_like_mut()
+    {
+      for (_, target) in targets.iter_mut() {
+        if let Some(target) = target.as_table_like_mut() {
+          for (key, item) in target.iter_mut() {
+            if DEPENDENCY_TABLES.contains(&key.get()) {
+              tables.push(item);
+            }
+          }
         }
       }
-
-      new_lines.push(current_line.to_string());
     }
+  }
+
+  for table in tables.into_iter().filter_map(Item::as_table_like_mut) {
+    update_table(table, crates, version);
+  }
+}
 
-    let new_content = new_lines.join("\n");
-    if new_content != content {
-      std::fs::write(&self.path, new_content)
-        .with_context(|| format!("Failed to write cargo file: {}", self.path.display()))?;
+fn update_table(table: &mut dyn TableLike, crates: &[String], version: &semver::Version) {
+  for (key, dependency) in table.iter_mut() {
+    let Some(dependency) = dependency.as_table_like_mut() else {
+      continue;
+    };
+    let name = dependency
+      .get("package")
+      .and_then(Item::as_str)
+      .unwrap_or(key.get())
+      .to_string();
+    if !dependency.contains_key("path") || !crates.contains(&name) {
+      continue;
+    }
+    if let Some(item) = dependency.get_mut("version")
+      && let Some(requirement) = item.as_str().and_then(|old| requirement(old, version))
+    {
+      set_string(item, &requirement);
     }
+  }
+}
 
-    Ok(())
+/// `old` pointed at `version`, keeping a leading `^`, `~` or `=`. Other
+/// requirements, like ranges and wildcards, are left alone.
+fn requirement(old: &str, version: &semver::Version) -> Option<String> {
+  let operator = old
+    .trim_start()
+    .chars()
+    .next()
+    .filter(|char| matches!(char, '^' | '~' | '='))
+    .map_or(String::new(), String::from);
+  let rest = old.trim_start().trim_start_matches(['^', '~', '=']).trim();
+  if rest.is_empty()
+    || !rest
+      .chars()
+      .all(|char| char.is_ascii_alphanumeric() || ".-+".contains(char))
+  {
+    return None;
   }
+
+  // Build metadata has no meaning in requirements.
+  let version = semver::Version {
+    build: semver::BuildMetadata::EMPTY,
+    ..version.clone()
+  };
+  Some(format!("{}{}", operator, version))
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+  let normalize = |path: &Path| {
+    path
+      .components()
+      .filter(|component| !matches!(component, std::path::Component::CurDir))
+      .collect::<PathBuf>()
+  };
+  normalize(a) == normalize(b)
 }
@@ -0,0 +1,377 @@
+//! `CHANGELOG.md` sections in the [Keep a Changelog](https://keepachangelog.com) format, built
+//! from the Conventional Commits between version tags.
+
+use super::{FileStatus, conventional::ConventionalCommit};
+use crate::error::{Error, Result};
+use crate::git::{Commit, Repository};
+use std::path::Path;
+
+/// Default changelog file name.
+pub const FILE_NAME: &str = "CHANGELOG.md";
+
+/// Heading of the section collecting the commits that are not released yet.
+pub const UNRELEASED: &str = "Unreleased";
+
+const HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// Groups of a section, in order, with the commit types they collect.
+const GROUPS: [(&str, &[&str]); 3] = [
+  ("Added", &["feat"]),
+  ("Changed", &["perf", "refactor"]),
+  ("Fixed", &["fix"]),
+];
+
+/// One section of the changelog: a version, or [`UNRELEASED`].
+#[derive(Clone, Debug)]
+pub struct Release {
+  pub heading: String,
+  /// Release date as `YYYY-MM-DD`; unreleased sections have none.
+  pub date: Option<String>,
+  /// Commits of the release, newest first as listed by git.
+  pub commits: Vec<Commit>,
+}
+
+impl Release {
+  /// Section for the commits since the last version tag, released as
+  /// `heading` today unless it is [`UNRELEASED`].
+  ///
+  /// When `v<heading>` is already the last tag, the section covers the
+  /// commits of that tag instead, so it can be regenerated after tagging.
+  pub fn pending(repository: &Repository, heading: &str) -> Result<Self> {
+    let mut tags = repository.version_tags("v")?;
+    let tag_name = format!("v{}", heading);
+    if tags.last().is_some_and(|(tag, _)| *tag == tag_name) {
+      tags.pop();
+      return Ok(Self {
+        heading: heading.to_string(),
+        date: Some(repository.date(&tag_name)?),
+        commits: repository.commits(tags.last().map(|(tag, _)| tag.as_str()), &tag_name)?,
+      });
+    }
+
+    Ok(Self {
+      heading: heading.to_string(),
+      date: (heading != UNRELEASED).then(today),
+      commits: repository.commits(tags.last().map(|(tag, _)| tag.as_str()), "HEAD")?,
+    })
+  }
+
+  /// Every release of the repository, newest first: one per version tag,
+  /// preceded by the untagged commits released as `heading`, if any.
+  pub fn history(repository: &Repository, heading: &str) -> Result<Vec<Self>> {
+    let tags = repository.version_tags("v")?;
+    let mut releases = Vec::new();
+    let mut previous: Option<&str> = None;
+    for (tag, version) in &tags {
+      releases.push(Self {
+        heading: version.to_string(),
+        date: Some(repository.date(tag)?),
+        commits: repository.commits(previous, tag)?,
+      });
+      previous = Some(tag);
+    }
+
+    let pending = Self::pending(repository, heading)?;
+    if !pending.commits.is_empty() && !tags.iter().any(|(tag, _)| *tag == format!("v{}", heading)) {
+      releases.push(pending);
+    }
+    releases.reverse();
+    Ok(releases)
+  }
+
+  /// Markdown of the section, ending with a blank line.
+  pub fn render(&self) -> String {
+    self.render_with(Vec::new())
+  }
+
+  /// Markdown of the section listing the entries of `groups`, kept from
+  /// earlier sections, before the generated ones.
+  fn render_with(&self, mut groups: Vec<(Option<String>, Vec<String>)>) -> String {
+    let mut section = match &self.date {
+      Some(date) => format!("## [{}] - {}\n", self.heading, date),
+      None => format!("## [{}]\n", self.heading),
+    };
+
+    let commits: Vec<(&Commit, ConventionalCommit)> = self
+      .commits
+      .iter()
+      .rev()
+      .filter_map(|commit| Some((commit, ConventionalCommit::parse(&commit.message)?)))
+      .collect();
+    for (group, _) in GROUPS {
+      let entries = commits
+        .iter()
+        .filter(|(_, parsed)| group_of(parsed) == Some(group))
+        .map(|(commit, parsed)| entry(commit, parsed));
+      match groups
+        .iter_mut()
+        .find(|(name, _)| name.as_deref() == Some(group))
+      {
+        Some((_, kept)) => kept.extend(entries),
+        None => groups.push((Some(group.to_string()), entries.collect())),
+      }
+    }
+    // Entries outside of any group first, then the usual groups in order.
+    groups.sort_by_key(|(name, _)| match name {
+      None => 0,
+      Some(name) => {
+        1 + GROUPS
+          .iter()
+          .position(|(group, _)| group == name)
+          .unwrap_or(GROUPS.len())
+      }
+    });
+
+    for (group, entries) in groups {
+      if entries.is_empty() {
+        continue;
+      }
+      match group {
+        Some(group) => section.push_str(&format!("\n### {}\n\n", group)),
+        None => section.push('\n'),
+      }
+      for entry in entries {
+        section.push_str(&entry);
+        section.push('\n');
+      }
+    }
+    section.push('\n');
+    section
+  }
+}
+
+/// Group `commit` is listed under; breaking changes of other types count as
+/// changes.
+fn group_of(commit: &ConventionalCommit) -> Option<&'static str> {
+  GROUPS
+    .iter()
+    .find(|(_, kinds)| kinds.contains(&commit.kind.as_str()))
+    .map(|(group, _)| *group)
+    .or(commit.breaking.then_some("Changed"))
+}
+
+fn entry(commit: &Commit, parsed: &ConventionalCommit) -> String {
+  let mut entry = String::from("- ");
+  if parsed.breaking {
+    entry.push_str("**Breaking:** ");
+  }
+  if let Some(scope) = parsed.scope.as_deref().filter(|scope| !scope.is_empty()) {
+    entry.push_str(&format!("**{}:** ", scope));
+  }
+  let hash = commit.hash.get(..7).unwrap_or(&commit.hash);
+  entry.push_str(&format!("{} ({})", parsed.description, hash));
+  entry
+}
+
+/// `content` with `release` inserted as its newest section, replacing a
+/// section with the same heading and the [`UNRELEASED`] one it supersedes.
+/// The entries written by hand in those are moved into `release`, while the
+/// generated ones are made again from its commits. An empty `content` gets
+/// the standard Keep a Changelog header.
+pub fn insert(content: &str, release: &Release) -> String {
+  let (preamble, sections) = split(content);
+  let superseded = |heading: &str| heading == release.heading || heading == UNRELEASED;
+
+  let mut kept: Vec<(Option<String>, Vec<String>)> = Vec::new();
+  for (_, section) in sections.iter().filter(|(heading, _)| superseded(heading)) {
+    for (group, entries) in entries(section) {
+      let entries = entries.into_iter().filter(|entry| !is_generated(entry));
+      match kept.iter_mut().find(|(name, _)| *name == group) {
+        Some((_, kept)) => kept.extend(entries),
+        None => kept.push((group, entries.collect())),
+      }
+    }
+  }
+
+  let mut changelog = preamble;
+  changelog.push_str(&release.render_with(kept));
+  for (heading, section) in sections {
+    if !superseded(heading) {
+      changelog.push_str(section);
+    }
+  }
+  changelog
+}
+
+/// Entries of a section by `### ` group, in order; entries before the first
+/// group have none. An entry keeps the indented lines continuing it.
+fn entries(section: &str) -> Vec<(Option<String>, Vec<String>)> {
+  let mut groups: Vec<(Option<String>, Vec<String>)> = Vec::new();
+  for line in section.lines().skip(1) {
+    if let Some(group) = line.strip_prefix("### ") {
+      groups.push((Some(group.trim().to_string()), Vec::new()));
+      continue;
+    }
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    if groups.is_empty() {
+      groups.push((None, Vec::new()));
+    }
+    let entries = &mut groups.last_mut().expect("a group was pushed").1;
+    match entries.last_mut() {
+      Some(entry) if line.starts_with(char::is_whitespace) => {
+        entry.push('\n');
+        entry.push_str(line);
+      }
+      _ => entries.push(line.to_string()),
+    }
+  }
+  groups
+}
+
+/// Whether `entry` was generated from a commit, ending with its short hash.
+fn is_generated(entry: &str) -> bool {
+  entry
+    .strip_suffix(')')
+    .and_then(|rest| rest.rsplit_once(" ("))
+    .is_some_and(|(_, hash)| hash.len() == 7 && hash.bytes().all(|byte| byte.is_ascii_hexdigit()))
+}
+
+/// `content` with all of its sections replaced by `releases`, keeping the
+/// text before the first section.
+pub fn regenerate(content: &str, releases: &[Release]) -> String {
+  let (mut changelog, _) = split(content);
+  for release in releases {
+    changelog.push_str(&release.render());
+  }
+  changelog
+}
+
+/// Splits a changelog into the text before its first `## ` heading, ending
+/// with a blank line, and its sections with their bracket-less headings.
+fn split(content: &str) -> (String, Vec<(&str, &str)>) {
+  let starts: Vec<usize> = content
+    .match_indices("## ")
+    .map(|(start, _)| start)
+    .filter(|start| *start == 0 || content[..*start].ends_with('\n'))
+    .collect();
+  let Some(first) = starts.first() else {
+    return (preamble(content), Vec::new());
+  };
+
+  let sections = starts
+    .iter()
+    .enumerate()
+    .map(|(i, start)| {
+      let end = starts.get(i + 1).copied().unwrap_or(content.len());
+      let section = &content[*start..end];
+      let title = section.lines().next().unwrap_or_default();
+      let heading = title
+        .trim_start_matches("## ")
+        .split(" - ")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+      (heading, section)
+    })
+    .collect();
+  (preamble(&content[..*first]), sections)
+}
+
+fn preamble(text: &str) -> String {
+  let text = text.trim_end();
+  if text.is_empty() {
+    format!("{}\n", HEADER)
+  } else {
+    format!("{}\n\n", text)
+  }
+}
+
+/// Today's date as `YYYY-MM-DD`, honoring `SOURCE_DATE_EPOCH` for
+/// reproducible releases.
+pub(crate) fn today() -> String {
+  let seconds = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+    .unwrap_or_else(|| {
+      std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+    });
+
+  // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`.
+  let days = seconds.div_euclid(86_400) + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Inserts `release` into the changelog at `path`, created if missing, the
+/// way `wucc version` writes version files.
+pub(crate) fn write_release(
+  path: &Path,
+  release: &Release,
+  args: &crate::args::VersionArgs,
+  plain: bool,
+) -> Result<(FileStatus, String)> {
+  let content = read(path)?;
+  let patched = insert(&content, release);
+  super::run::apply(
+    path,
+    &content,
+    &patched,
+    args.dry_run,
+    args.interactive,
+    plain,
+  )
+}
+
+/// Runs the `changelog` subcommand.
+pub(crate) fn run(args: crate::args::ChangelogArgs) -> Result<()> {
+  let repository = Repository::discover(Path::new("."))?;
+  let heading = match args.unreleased {
+    true => UNRELEASED.to_string(),
+    false => super::run::min_version_present()?.to_string(),
+  };
+
+  let content = read(&args.output)?;
+  let (patched, detail) = match args.regenerate {
+    true => {
+      let releases = Release::history(&repository, &heading)?;
+      let detail = format!("{} release(s)", releases.len());
+      (regenerate(&content, &releases), detail)
+    }
+    false => (
+      insert(&content, &Release::pending(&repository, &heading)?),
+      heading,
+    ),
+  };
+
+  let (status, diff) =
+    super::run::apply(&args.output, &content, &patched, args.dry_run, false, true)?;
+  super::run::print_output(&args.output, &detail, status);
+  if args.dry_run {
+    super::run::print_diff(&diff);
+  }
+  Ok(())
+}
+
+/// Content of the changelog at `path`, empty if there is none yet.
+fn read(path: &Path) -> Result<String> {
+  match std::fs::read_to_string(path) {
+    Ok(content) => Ok(content),
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+    Err(error) => Err(Error::io(path)(error)),
+  }
+}
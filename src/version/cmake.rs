@@ -1,5 +1,12 @@
-use anyhow::{Context, Result};
-use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+/// Variables followed from `project(... VERSION ${VAR})` to the `set()` giving
+/// the version, through `set(VAR ${OTHER})` indirections.
+const MAX_INDIRECTIONS: usize = 8;
 
 pub struct CmakeFile {
   path: PathBuf,
@@ -12,54 +19,341 @@ impl super::VersionIO for CmakeFile {
     }
   }
 
-  fn new_auto() -> Result<Self> {
+  fn new_auto_in(dir: &Path) -> Result<Self> {
     let path_candidates = vec!["CMakeLists.txt"];
     let mut path = None;
     for path_candidate in path_candidates {
-      let path_candidate = PathBuf::from(path_candidate);
+      let path_candidate = dir.join(path_candidate);
       if path_candidate.exists() {
         path = Some(path_candidate);
         break;
       }
     }
     Ok(Self::new(
-      path.context("Failed to find cmake file")?.as_path(),
+      path
+        .ok_or_else(|| Error::NotFound("CMakeLists.txt not found".to_string()))?
+        .as_path(),
     ))
   }
 
+  fn kind(&self) -> &'static str {
+    "cmake"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn supports_prerelease(&self) -> bool {
+    false
+  }
+
+  /// Reads `major[.minor[.patch[.tweak]]]`, missing components being 0 and
+  /// the tweak becoming build metadata: `1.2.3.4` is `1.2.3+4`.
   fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read cmake file: {}", self.path.display()))?;
-
-    let re = regex::Regex::new(r#"(?i)\bproject\s*\(\s*\w+\s+(?:.*\s+)?VERSION\s+(\d+\.\d+\.\d+)"#)
-      .context("Failed to compile regex")?;
-
-    if let Some(captures) = re.captures(&content) {
-      let version_str = captures.get(1).unwrap().as_str();
-      semver::Version::parse(version_str)
-        .with_context(|| format!("Failed to parse version: {}", version_str))
-    } else {
-      Err(anyhow::anyhow!(
-        "VERSION field not found in project() section of CMakeLists.txt"
-      ))
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let commands = commands(&content);
+    from_cmake(version_argument(&commands)?.value)
+  }
+
+  /// Writes as many components as the file had, more when the version needs
+  /// them; a numeric build metadata is written as the tweak.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let commands = commands(content);
+    let argument = version_argument(&commands)?;
+    let components = match from_cmake(argument.value) {
+      Ok(_) => argument.value.split('.').count(),
+      Err(_) => 3,
+    };
+
+    // project(VERSION) only takes numeric components, so pre-release and
+    // build metadata cannot be stored here.
+    let mut patched = content.to_string();
+    patched.replace_range(argument.span.clone(), &to_cmake(version, components));
+    Ok(patched)
+  }
+}
+
+/// The version `text` of a `project()` call.
+fn from_cmake(text: &str) -> Result<semver::Version> {
+  let components: Vec<u64> = text
+    .split('.')
+    .map_while(|component| component.parse().ok())
+    .collect();
+  if !(1..=4).contains(&components.len()) || components.len() != text.split('.').count() {
+    return super::parse_version(text);
+  }
+
+  let component = |index: usize| components.get(index).copied().unwrap_or(0);
+  let mut version = semver::Version::new(component(0), component(1), component(2));
+  if let Some(tweak) = components.get(3) {
+    version.build =
+      semver::BuildMetadata::new(&tweak.to_string()).expect("a number is valid metadata");
+  }
+  Ok(version)
+}
+
+/// `version` with at least `components` components.
+fn to_cmake(version: &semver::Version, components: usize) -> String {
+  let tweak = version.build.as_str().parse::<u64>().ok();
+  let needed = match (tweak, version.patch, version.minor) {
+    (Some(_), _, _) => 4,
+    (None, 1.., _) => 3,
+    (None, 0, 1..) => 2,
+    _ => 1,
+  };
+  [
+    version.major,
+    version.minor,
+    version.patch,
+    tweak.unwrap_or(0),
+  ][..components.max(needed)]
+    .iter()
+    .map(u64::to_string)
+    .collect::<Vec<_>>()
+    .join(".")
+}
+
+/// A command invocation like `project(Foo VERSION 1.2)`.
+struct Command<'a> {
+  /// Lowercase name; CMake command names are case-insensitive.
+  name: String,
+  arguments: Vec<Argument<'a>>,
+  /// Offset of the name in the file.
+  start: usize,
+  /// Number of `if()`, `function()` and similar blocks the call is in.
+  depth: usize,
+}
+
+struct Argument<'a> {
+  /// Text of the argument, without its quotes or brackets.
+  value: &'a str,
+  span: Range<usize>,
+  /// Whether the argument is quoted or bracketed, and so never a keyword.
+  quoted: bool,
+}
+
+/// The argument holding the version of the top-level project: the `VERSION`
+/// of the first `project()` outside of any block, or the `set()` of the
+/// variable it references.
+fn version_argument<'a>(commands: &'a [Command<'a>]) -> Result<&'a Argument<'a>> {
+  let not_found = || {
+    Error::NotFound("VERSION field not found in project() section of CMakeLists.txt".to_string())
+  };
+
+  let projects = || commands.iter().filter(|command| command.name == "project");
+  let project = projects()
+    .find(|command| command.depth == 0)
+    .or_else(|| projects().next())
+    .ok_or_else(not_found)?;
+  let mut argument = project
+    .arguments
+    .windows(2)
+    .find(|pair| !pair[0].quoted && pair[0].value == "VERSION")
+    .map(|pair| &pair[1])
+    .ok_or_else(not_found)?;
+
+  let mut before = project.start;
+  for _ in 0..MAX_INDIRECTIONS {
+    let Some(variable) = argument
+      .value
+      .strip_prefix("${")
+      .and_then(|value| value.strip_suffix('}'))
+      .filter(|name| {
+        name
+          .bytes()
+          .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+      })
+    else {
+      return Ok(argument);
+    };
+    let set = commands
+      .iter()
+      .rev()
+      .filter(|command| command.start < before && command.name == "set")
+      .find(|command| {
+        command
+          .arguments
+          .first()
+          .is_some_and(|name| name.value == variable)
+      })
+      .filter(|command| command.arguments.len() > 1)
+      .ok_or_else(|| {
+        Error::NotFound(format!(
+          "Variable '{}' used as project() VERSION is not set in CMakeLists.txt",
+          variable
+        ))
+      })?;
+    argument = &set.arguments[1];
+    before = set.start;
+  }
+  Err(not_found())
+}
+
+/// The command invocations of a CMake file, skipping comments.
+fn commands(content: &str) -> Vec<Command<'_>> {
+  let mut scanner = Scanner {
+    text: content,
+    pos: 0,
+  };
+  let mut commands = Vec::new();
+  let mut depth = 0usize;
+
+  while let Some(byte) = scanner.skip_blank() {
+    if !(byte.is_ascii_alphabetic() || byte == b'_') {
+      scanner.pos += 1;
+      continue;
+    }
+    let start = scanner.pos;
+    while scanner
+      .peek()
+      .is_some_and(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+    {
+      scanner.pos += 1;
+    }
+    let name = content[start..scanner.pos].to_ascii_lowercase();
+    while matches!(scanner.peek(), Some(b' ' | b'\t')) {
+      scanner.pos += 1;
+    }
+    if scanner.peek() != Some(b'(') {
+      continue;
+    }
+    scanner.pos += 1;
+    let arguments = scanner.arguments();
+
+    if let Some(block) = name.strip_prefix("end")
+      && BLOCKS.contains(&block)
+    {
+      depth = depth.saturating_sub(1);
+    }
+    let opens_block = BLOCKS.contains(&name.as_str());
+    commands.push(Command {
+      name,
+      arguments,
+      start,
+      depth,
+    });
+    if opens_block {
+      depth += 1;
     }
   }
+  commands
+}
 
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read CMakeLists.txt: {}", self.path.display()))?;
+/// Commands opening a block closed by `end<command>()`.
+const BLOCKS: [&str; 6] = ["if", "foreach", "while", "function", "macro", "block"];
 
-    let re =
-      regex::Regex::new(r#"(?i)(\bproject\s*\(\s*\w+\s+(?:.*\s+)?VERSION\s+)(\d+\.\d+\.\d+)"#)
-        .context("Failed to compile regex")?;
+struct Scanner<'a> {
+  text: &'a str,
+  pos: usize,
+}
 
-    let new_content = re.replace(&content, format!("${{1}}{}", version));
+impl<'a> Scanner<'a> {
+  /// Arguments up to the closing parenthesis of the call, which is consumed.
+  fn arguments(&mut self) -> Vec<Argument<'a>> {
+    let mut arguments = Vec::new();
+    let mut depth = 1;
+    while let Some(byte) = self.skip_blank() {
+      match byte {
+        b'(' => {
+          depth += 1;
+          self.pos += 1;
+        }
+        b')' => {
+          self.pos += 1;
+          depth -= 1;
+          if depth == 0 {
+            break;
+          }
+        }
+        b'"' => {
+          let start = self.pos + 1;
+          self.pos = start;
+          while let Some(byte) = self.peek() {
+            match byte {
+              b'\\' => self.pos += 2,
+              b'"' => break,
+              _ => self.pos += 1,
+            }
+          }
+          let end = self.pos.min(self.text.len());
+          self.pos = end + 1;
+          arguments.push(self.argument(start..end, true));
+        }
+        b'[' if self.bracket_open().is_some() => {
+          let span = self.bracket();
+          arguments.push(self.argument(span, true));
+        }
+        _ => {
+          let start = self.pos;
+          while let Some(byte) = self.peek() {
+            match byte {
+              b'\\' => self.pos += 2,
+              b'(' | b')' | b'#' | b'"' => break,
+              _ if byte.is_ascii_whitespace() => break,
+              _ => self.pos += 1,
+            }
+          }
+          let end = self.pos.min(self.text.len());
+          self.pos = end;
+          arguments.push(self.argument(start..end, false));
+        }
+      }
+    }
+    arguments
+  }
+
+  fn argument(&self, span: Range<usize>, quoted: bool) -> Argument<'a> {
+    Argument {
+      value: &self.text[span.clone()],
+      span,
+      quoted,
+    }
+  }
 
-    if new_content != content {
-      std::fs::write(&self.path, new_content.as_bytes())
-        .with_context(|| format!("Failed to write CMakeLists.txt: {}", self.path.display()))?;
+  /// Skips whitespace and comments, returning the next byte.
+  fn skip_blank(&mut self) -> Option<u8> {
+    loop {
+      let byte = self.peek()?;
+      if byte.is_ascii_whitespace() {
+        self.pos += 1;
+      } else if byte == b'#' {
+        self.pos += 1;
+        if self.peek() == Some(b'[') && self.bracket_open().is_some() {
+          self.bracket();
+        } else {
+          self.pos = self.text[self.pos..]
+            .find('\n')
+            .map_or(self.text.len(), |end| self.pos + end);
+        }
+      } else {
+        return Some(byte);
+      }
     }
+  }
+
+  /// Number of `=` of the `[==[` opening a bracket argument or comment at the
+  /// current position.
+  fn bracket_open(&self) -> Option<usize> {
+    let rest = self.text[self.pos..].strip_prefix('[')?;
+    let equals = rest.len() - rest.trim_start_matches('=').len();
+    rest[equals..].starts_with('[').then_some(equals)
+  }
+
+  /// Moves past the bracket argument or comment at the current position,
+  /// returning the span of its contents.
+  fn bracket(&mut self) -> Range<usize> {
+    let equals = self.bracket_open().expect("at a bracket");
+    let start = self.pos + equals + 2;
+    let close = format!("]{}]", "=".repeat(equals));
+    let end = self.text[start..]
+      .find(&close)
+      .map_or(self.text.len(), |end| start + end);
+    self.pos = (end + close.len()).min(self.text.len());
+    start..end
+  }
 
-    Ok(())
+  fn peek(&self) -> Option<u8> {
+    self.text.as_bytes().get(self.pos).copied()
   }
 }
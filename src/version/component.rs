@@ -0,0 +1,226 @@
+//! Independently versioned components of a monorepo.
+
+use super::{HeaderSearch, VersionIO};
+use crate::error::{Error, Result};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+/// Name of the component formed by the version files of the root directory.
+pub const ROOT: &str = ".";
+
+/// A directory holding version files of its own, along with everything below it.
+pub struct Component {
+  /// Directory name, or the path from the root when several components share it.
+  pub name: String,
+  /// Location, below the directory the components were discovered in.
+  pub dir: PathBuf,
+  pub files: Vec<(String, Box<dyn VersionIO>)>,
+  /// Locations of the components below this one, which is only the case for
+  /// the root.
+  pub nested: Vec<PathBuf>,
+}
+
+impl Component {
+  /// Every component below `root`, the root first and the others sorted by
+  /// path.
+  ///
+  /// A subdirectory is a component when it holds a readable version file other
+  /// than an export header, which is found below the directory searched as
+  /// `headers` says. The search does not descend into components, nor into the
+  /// directories skipped when looking for headers. The root is a component when
+  /// it has version files outside of the others.
+  pub fn discover(root: &Path, headers: &HeaderSearch) -> Result<Vec<Self>> {
+    let tree = Tree::walk(root, headers);
+    let mut components = Vec::new();
+    tree.collect(root, headers, &mut components);
+
+    let root_files: Vec<_> = tree
+      .versioned(root, headers)
+      .into_iter()
+      .filter(|(_, file)| {
+        !components
+          .iter()
+          .any(|component: &Self| within(file.path(), &component.dir))
+      })
+      .collect();
+
+    for component in &mut components {
+      component.name = dir_name(&component.dir);
+    }
+    let names: Vec<String> = components.iter().map(|c| c.name.clone()).collect();
+    for component in &mut components {
+      if names.iter().filter(|name| **name == component.name).count() > 1 {
+        component.name = relative(root, &component.dir);
+      }
+    }
+
+    if !root_files.is_empty() {
+      components.insert(
+        0,
+        Self {
+          name: ROOT.to_string(),
+          dir: root.to_path_buf(),
+          files: root_files,
+          nested: components.iter().map(|c| c.dir.clone()).collect(),
+        },
+      );
+    }
+    Ok(components)
+  }
+
+  /// The component below `root` called `name`, or found at the path `name`.
+  pub fn find(root: &Path, name: &str, headers: &HeaderSearch) -> Result<Self> {
+    let components = Self::discover(root, headers)?;
+    let names: Vec<String> = components.iter().map(|c| c.name.clone()).collect();
+    let path = Path::new(name);
+    components
+      .into_iter()
+      .find(|component| component.name == name || component.dir == path)
+      .ok_or_else(|| {
+        Error::NotFound(format!(
+          "Component '{}' not found; components: {}",
+          name,
+          match names.is_empty() {
+            true => "none".to_string(),
+            false => names.join(", "),
+          }
+        ))
+      })
+  }
+
+  /// Prefix of the version tags of the component: `<name>/v`, or `v` for the root.
+  pub fn tag_prefix(&self) -> String {
+    match self.name == ROOT {
+      true => "v".to_string(),
+      false => format!("{}/v", self.name),
+    }
+  }
+
+  /// Whether `path` belongs to the component rather than to one nested in it.
+  pub fn owns(&self, path: &Path) -> bool {
+    within(path, &self.dir) && !self.nested.iter().any(|dir| within(path, dir))
+  }
+
+  /// Directory to limit the commit history to, unless it is the root.
+  pub fn history_path(&self) -> Option<&Path> {
+    (self.name != ROOT).then_some(self.dir.as_path())
+  }
+}
+
+/// The directories below a root and the files among theirs that may be export
+/// headers, from a single walk.
+struct Tree {
+  /// Subdirectories of each directory, sorted by name.
+  subdirs: HashMap<PathBuf, Vec<PathBuf>>,
+  /// Files named like export headers, in walk order, so that those below a
+  /// directory follow each other.
+  headers: Vec<PathBuf>,
+  /// Index in `headers` of the first one each directory may hold.
+  first_header: HashMap<PathBuf, usize>,
+}
+
+impl Tree {
+  /// Walks `root`, skipping the directories the export header search skips.
+  fn walk(root: &Path, headers: &HeaderSearch) -> Self {
+    let listing = match root.as_os_str().is_empty() {
+      true => Path::new("."),
+      false => root,
+    };
+    let mut tree = Self {
+      subdirs: HashMap::new(),
+      headers: Vec::new(),
+      first_header: HashMap::from([(root.to_path_buf(), 0)]),
+    };
+    let entries = super::export_header::walk(listing)
+      .build()
+      .filter_map(|entry| entry.ok());
+    for entry in entries.filter(|entry| entry.depth() > 0) {
+      let Ok(relative) = entry.path().strip_prefix(listing) else {
+        continue;
+      };
+      let path = root.join(relative);
+      match entry.file_type() {
+        Some(kind) if kind.is_dir() => {
+          let parent = path.parent().unwrap_or(root).to_path_buf();
+          tree.first_header.insert(path.clone(), tree.headers.len());
+          tree.subdirs.entry(parent).or_default().push(path);
+        }
+        Some(kind)
+          if kind.is_file()
+            && super::export_header::is_candidate(
+              headers,
+              &entry.file_name().to_string_lossy(),
+            ) =>
+        {
+          tree.headers.push(path)
+        }
+        _ => {}
+      }
+    }
+    tree
+  }
+
+  /// Collects the components below `dir` into `components`, unnamed.
+  fn collect(&self, dir: &Path, headers: &HeaderSearch, components: &mut Vec<Component>) {
+    for subdir in self.subdirs.get(dir).into_iter().flatten() {
+      let files = self.versioned(subdir, headers);
+      if files.iter().any(|(_, file)| file.kind() != "export-header") {
+        components.push(Component {
+          name: String::new(),
+          dir: subdir.clone(),
+          files,
+          nested: Vec::new(),
+        });
+      } else {
+        self.collect(subdir, headers, components);
+      }
+    }
+  }
+
+  /// The files below `dir` that may be export headers.
+  fn headers_below(&self, dir: &Path) -> &[PathBuf] {
+    let first = self
+      .first_header
+      .get(dir)
+      .copied()
+      .unwrap_or(self.headers.len());
+    let count = self.headers[first..]
+      .iter()
+      .take_while(|path| within(path, dir))
+      .count();
+    &self.headers[first..first + count]
+  }
+
+  /// Version files detected in `dir` that hold a readable version.
+  fn versioned(&self, dir: &Path, headers: &HeaderSearch) -> Vec<(String, Box<dyn VersionIO>)> {
+    let header_paths = super::export_header::find_among(dir, headers, self.headers_below(dir));
+    <dyn VersionIO>::detect_with_headers(dir, header_paths, headers.all)
+      .into_iter()
+      .filter(|(_, file)| file.read().is_ok())
+      .collect()
+  }
+}
+
+/// Whether `path` lies in `dir`, also when it starts with `./`.
+pub(super) fn within(path: &Path, dir: &Path) -> bool {
+  path.strip_prefix(".").unwrap_or(path).starts_with(dir)
+}
+
+fn dir_name(dir: &Path) -> String {
+  dir
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default()
+}
+
+/// `dir` relative to `root`, with `/` separators on every platform.
+fn relative(root: &Path, dir: &Path) -> String {
+  let dir = dir.strip_prefix(root).unwrap_or(dir);
+  dir
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
@@ -1,115 +1,291 @@
-use anyhow::{Context, Result};
-use std::path::{Path, PathBuf};
+use super::python::{self, Kind, Line, Token};
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
 
+/// Conan recipe, holding the version either as a `version` class attribute
+/// or as a `self.version = "..."` assignment, typically in `set_version()`.
 pub struct ConanFile {
   path: PathBuf,
 }
 
-impl super::VersionIO for ConanFile {
-  fn new(path: &Path) -> Self {
-    Self {
-      path: path.to_path_buf(),
-    }
-  }
+/// How the recipe class sets its version.
+enum Assignment<'a> {
+  /// A string literal, given by the span of its contents.
+  Literal(Range<usize>),
+  /// Any other expression, like `get_version()`.
+  Dynamic(&'a str),
+}
 
-  fn new_auto() -> Result<Self> {
-    let path_candidates = vec!["conanfile.py"];
-    let mut path = None;
-    for path_candidate in path_candidates {
-      let path_candidate = PathBuf::from(path_candidate);
-      if path_candidate.exists() {
-        path = Some(path_candidate);
-        break;
+impl ConanFile {
+  /// The version assignment of the recipe class: the last class of the file
+  /// deriving from `ConanFile`, directly or through another class, falling
+  /// back to its bases only when it assigns no version. Literals win over
+  /// computed versions of the same class.
+  fn locate<'a>(&self, content: &'a str) -> Option<Assignment<'a>> {
+    let lines = python::lines(content);
+    let mut bases = conan_file_names(&lines);
+    let mut recipes = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+      if let Some((name, class_bases)) = class(line)
+        && class_bases.iter().any(|base| bases.contains(base))
+      {
+        bases.push(name.to_string());
+        recipes.push(index);
       }
     }
-    Ok(Self::new(
-      path.context("Failed to find conan file")?.as_path(),
-    ))
+
+    recipes.into_iter().rev().find_map(|index| {
+      let class_indent = lines[index].indent;
+      let body = lines[index + 1..]
+        .iter()
+        .take_while(|line| line.indent > class_indent);
+      let body_indent = body.clone().next().map_or(0, |line| line.indent);
+      let assignments: Vec<Assignment> = body
+        .filter_map(|line| version_assignment(content, line, body_indent))
+        .collect();
+      let literal = assignments
+        .iter()
+        .position(|assignment| matches!(assignment, Assignment::Literal(_)));
+      let mut assignments = assignments.into_iter();
+      match literal {
+        Some(index) => assignments.nth(index),
+        None => assignments.next(),
+      }
+    })
   }
 
-  fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read conanfile: {}", self.path.display()))?;
+  /// Span of the version literal, or why there is none.
+  fn literal(&self, content: &str) -> Result<Range<usize>> {
+    match self.locate(content) {
+      Some(Assignment::Literal(span)) => Ok(span),
+      Some(Assignment::Dynamic(expression)) => Err(Error::NotFound(format!(
+        "The version of {} is computed by `{}` and cannot be written",
+        crate::compilers::common::display_path(&self.path, None),
+        expression
+      ))),
+      None => Err(Self::not_found()),
+    }
+  }
 
-    let re = regex::Regex::new(r#"(?m)^\s*version\s*=\s*"([^"]+)"\s*$"#)
-      .context("Failed to compile regex")?;
-    let mut in_conan_class = false;
+  fn not_found() -> Error {
+    Error::NotFound("Version field not found in ConanFile class".to_string())
+  }
+}
 
-    for line in content.lines() {
-      if line.trim().starts_with("class") && line.contains("ConanFile") {
-        in_conan_class = true;
-        continue;
+/// Names `ConanFile` is known by in the recipe, including aliases like
+/// `from conan import ConanFile as Recipe` or `import conan as c`.
+fn conan_file_names(lines: &[Line]) -> Vec<String> {
+  let mut names: Vec<String> = ["ConanFile", "conan.ConanFile", "conans.ConanFile"]
+    .map(str::to_string)
+    .to_vec();
+  for line in lines {
+    let texts: Vec<&str> = line
+      .tokens
+      .iter()
+      .map(|token| token.text)
+      .filter(|text| !matches!(*text, "(" | ")"))
+      .collect();
+    match texts.as_slice() {
+      ["from", "conan" | "conans", "import", imported @ ..] => {
+        for item in imported.split(|text| *text == ",") {
+          if let ["ConanFile", "as", alias] = item {
+            names.push(alias.to_string());
+          }
+        }
       }
-
-      if line.trim().starts_with("class") && in_conan_class {
-        in_conan_class = false;
-        if line.contains("ConanFile") {
-          in_conan_class = true;
+      ["import", imported @ ..] => {
+        for item in imported.split(|text| *text == ",") {
+          if let ["conan" | "conans", "as", alias] = item {
+            names.push(format!("{}.ConanFile", alias));
+          }
         }
-        continue;
       }
+      _ => {}
+    }
+  }
+  names
+}
 
-      if in_conan_class {
-        if let Some(captures) = re.captures(line) {
-          let version_str = captures.get(1).unwrap().as_str();
-          return semver::Version::parse(version_str)
-            .with_context(|| format!("Failed to parse version: {}", version_str));
-        }
+/// Name and base classes of a `class Name(Base, module.Base):` line.
+fn class<'a>(line: &Line<'a>) -> Option<(&'a str, Vec<String>)> {
+  let [class, name, rest @ ..] = line.tokens.as_slice() else {
+    return None;
+  };
+  if !class.is(Kind::Name, "class") || name.kind != Kind::Name {
+    return None;
+  }
+
+  let mut bases = Vec::new();
+  let mut base = String::new();
+  let mut depth = 0;
+  for token in rest {
+    match token.text {
+      "(" | "[" | "{" => depth += 1,
+      ")" | "]" | "}" => depth -= 1,
+      "," | ":" if depth <= 1 => {
+        bases.push(std::mem::take(&mut base));
+        continue;
       }
+      text if depth == 1 => base.push_str(text),
+      _ => {}
+    }
+    if depth == 0 {
+      bases.push(std::mem::take(&mut base));
+      break;
+    }
+  }
+  bases.retain(|base| !base.is_empty() && !base.contains('='));
+  Some((name.text, bases))
+}
+
+/// The assignment of `version` in `line` of a class body whose statements
+/// start at `body_indent`: a class attribute, possibly annotated, or
+/// `self.version` in a method.
+fn version_assignment<'a>(
+  content: &'a str,
+  line: &Line<'a>,
+  body_indent: usize,
+) -> Option<Assignment<'a>> {
+  let tokens = line.tokens.as_slice();
+  let value: &[Token] = match tokens {
+    [version, rest @ ..] if line.indent == body_indent && version.is(Kind::Name, "version") => rest,
+    [object, dot, version, rest @ ..]
+      if object.is(Kind::Name, "self")
+        && dot.is(Kind::Op, ".")
+        && version.is(Kind::Name, "version") =>
+    {
+      rest
+    }
+    _ => return None,
+  };
+  // Skip the type hint of `version: str = "1.2.3"`.
+  let equals = match value.first()? {
+    token if token.is(Kind::Op, "=") => 0,
+    token if token.is(Kind::Op, ":") => value.iter().position(|token| token.is(Kind::Op, "="))?,
+    _ => return None,
+  };
+
+  match &value[equals + 1..] {
+    [] => None,
+    [string] if string.literal().is_some() => string.literal().map(Assignment::Literal),
+    [first, .., last] | [first @ last] => Some(Assignment::Dynamic(
+      &content[first.span.start..last.span.end],
+    )),
+  }
+}
+
+impl super::VersionIO for ConanFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  /// Finds `conanfile.py`, unless its version comes from `conandata.yml`, which
+  /// is then handled on its own.
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("conanfile.py");
+    if !path.exists() {
+      return Err(Error::NotFound("conanfile.py not found".to_string()));
     }
 
-    Err(anyhow::anyhow!(
-      "Version field not found in ConanFile class"
-    ))
+    let file = Self::new(&path);
+    let content = std::fs::read_to_string(&path).map_err(Error::io(&path))?;
+    if file.literal(&content).is_err() && super::ConanDataFile::new_auto_in(dir).is_ok() {
+      return Err(Error::NotFound(
+        "conanfile.py takes its version from conandata.yml".to_string(),
+      ));
+    }
+    Ok(file)
   }
 
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read conanfile: {}", self.path.display()))?;
+  fn kind(&self) -> &'static str {
+    "conan"
+  }
 
-    let re = regex::Regex::new(r#"(?m)^(\s*version\s*=\s*")[^"]+(".*)$"#)
-      .context("Failed to compile regex")?;
+  fn path(&self) -> &Path {
+    &self.path
+  }
 
-    let mut in_conan_class = false;
-    let mut new_lines = Vec::new();
-    let mut version_replaced = false;
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let span = self.literal(&content)?;
+    super::parse_version(&content[span])
+  }
 
-    for line in content.lines() {
-      let current_line = line;
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let span = self.literal(content)?;
+    let mut patched = content.to_string();
+    patched.replace_range(span, &version.to_string());
+    Ok(patched)
+  }
+}
 
-      if current_line.trim().starts_with("class") && current_line.contains("ConanFile") {
-        in_conan_class = true;
-        new_lines.push(current_line.to_string());
-        continue;
-      }
+/// `conandata.yml` with a top-level `version` key, read by the recipe.
+pub struct ConanDataFile {
+  path: PathBuf,
+}
 
-      if current_line.trim().starts_with("class") && in_conan_class {
-        in_conan_class = false;
-        if current_line.contains("ConanFile") {
-          in_conan_class = true;
-        }
-        new_lines.push(current_line.to_string());
-        continue;
-      }
+impl ConanDataFile {
+  fn regex() -> Result<regex::Regex> {
+    Ok(regex::Regex::new(
+      r#"(?m)^(version\s*:\s*["']?)([^"'\s#]+)(["']?[^\n]*)$"#,
+    )?)
+  }
 
-      if in_conan_class && !version_replaced {
-        if let Some(captures) = re.captures(current_line) {
-          let new_line = format!("{}{}{}", &captures[1], version, &captures[2]);
-          new_lines.push(new_line);
-          version_replaced = true;
-          continue;
-        }
-      }
+  fn not_found() -> Error {
+    Error::NotFound("Version field not found in conandata.yml".to_string())
+  }
+}
 
-      new_lines.push(current_line.to_string());
+impl super::VersionIO for ConanDataFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
     }
+  }
 
-    let new_content = new_lines.join("\n");
-    if new_content != content {
-      std::fs::write(&self.path, new_content)
-        .with_context(|| format!("Failed to write conanfile: {}", self.path.display()))?;
+  /// Finds `conandata.yml` when it holds a version; it usually only lists
+  /// sources.
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("conandata.yml");
+    let content = std::fs::read_to_string(&path)
+      .map_err(|_| Error::NotFound("conandata.yml not found".to_string()))?;
+    if !Self::regex()?.is_match(&content) {
+      return Err(Self::not_found());
     }
+    Ok(Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "conandata"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
 
-    Ok(())
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let captures = Self::regex()?
+      .captures(&content)
+      .ok_or_else(Self::not_found)?;
+    super::parse_version(&captures[2])
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let re = Self::regex()?;
+    if !re.is_match(content) {
+      return Err(Self::not_found());
+    }
+    Ok(
+      re.replacen(content, 1, |captures: &regex::Captures| {
+        format!("{}{}{}", &captures[1], version, &captures[3])
+      })
+      .to_string(),
+    )
   }
 }
@@ -0,0 +1,325 @@
+//! Version sources configured in `wucc.yml` or on the command line.
+
+use super::{
+  Component, ExportHeaderFile, HeaderSearch, LuaMetafile, RegexFile, VersionIO, YamlMetafile,
+  kind_name,
+};
+use crate::compilers::common::check_identifier;
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Configuration file read from the current directory when it exists.
+pub const FILE_NAME: &str = "wucc.yml";
+
+/// Contents of `wucc.yml`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  pub version: VersionConfig,
+}
+
+/// The `version` section of `wucc.yml`.
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct VersionConfig {
+  /// Whether to look for version files in their usual locations besides `sources`.
+  pub detect: bool,
+  /// Version files to handle; they replace detected files with the same path.
+  pub sources: Vec<Source>,
+  /// Kinds of version files to keep; all of them when empty.
+  pub only: Vec<String>,
+  /// Kinds of version files to leave alone.
+  pub exclude: Vec<String>,
+  /// Macro prefixes of the versions in export headers, each handled as a
+  /// version file of its own in the headers defining its macros; detected
+  /// when empty.
+  pub header_prefixes: Vec<String>,
+  /// Where to look for export headers.
+  pub headers: HeaderSearch,
+}
+
+/// A version file declared explicitly, as `kind:path` on the command line.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Source {
+  /// Kind of the file, e.g. `cmake`, or `regex` for arbitrary formats.
+  pub kind: String,
+  /// Path of the file, or a pattern where `*` and `?` match several files.
+  pub path: String,
+  /// Display name; defaults to the name of the kind.
+  #[serde(default)]
+  pub name: Option<String>,
+  /// Regular expression capturing the version, for `regex` sources.
+  #[serde(default)]
+  pub pattern: Option<String>,
+  /// Dotted path of the version, e.g. `plugin.version`, for `metafile` and
+  /// `lua-metafile` sources.
+  #[serde(default)]
+  pub key: Option<String>,
+  /// Macro prefix of the version, e.g. `FOO` for `FOO_VERSION_MAJOR`, for
+  /// `export-header` sources.
+  #[serde(default)]
+  pub prefix: Option<String>,
+}
+
+impl Default for VersionConfig {
+  fn default() -> Self {
+    Self {
+      detect: true,
+      sources: Vec::new(),
+      only: Vec::new(),
+      exclude: Vec::new(),
+      header_prefixes: Vec::new(),
+      headers: HeaderSearch::default(),
+    }
+  }
+}
+
+impl std::str::FromStr for Source {
+  type Err = String;
+
+  fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+    let (kind, path) = spec
+      .split_once(':')
+      .filter(|(_, path)| !path.is_empty())
+      .ok_or_else(|| format!("expected KIND:PATH, got '{}'", spec))?;
+    if kind_name(kind).is_none() {
+      return Err(format!("unknown version file kind '{}'", kind));
+    }
+    Ok(Self {
+      kind: kind.to_string(),
+      path: path.to_string(),
+      name: None,
+      pattern: None,
+      key: None,
+      prefix: None,
+    })
+  }
+}
+
+impl Config {
+  /// Reads the configuration at `path`, or `wucc.yml` when it exists.
+  pub fn load(path: Option<&Path>) -> Result<Self> {
+    let path = match path {
+      Some(path) => path,
+      None if Path::new(FILE_NAME).is_file() => Path::new(FILE_NAME),
+      None => return Ok(Self::default()),
+    };
+    let content = std::fs::read_to_string(path).map_err(Error::io(path))?;
+    serde_norway::from_str(&content).map_err(|source| Error::Yaml {
+      path: path.to_path_buf(),
+      source,
+    })
+  }
+
+  /// Applies the command line: `--file` replaces the whole configuration,
+  /// `--only`, `--exclude`, `--header-prefix`, `--header-name` and
+  /// `--all-headers` override their configured counterparts.
+  pub(crate) fn with_args(mut self, args: &crate::args::VersionArgs) -> Self {
+    if !args.file.is_empty() {
+      self.version = VersionConfig {
+        detect: false,
+        sources: args.file.clone(),
+        ..VersionConfig::default()
+      };
+    }
+    if !args.only.is_empty() {
+      self.version.only = args.only.clone();
+    }
+    if !args.exclude.is_empty() {
+      self.version.exclude = args.exclude.clone();
+    }
+    if !args.header_prefix.is_empty() {
+      self.version.header_prefixes = args.header_prefix.clone();
+    }
+    if !args.header_name.is_empty() {
+      self.version.headers.names = args.header_name.clone();
+    }
+    if args.all_headers {
+      self.version.headers.all = true;
+    }
+    self
+  }
+
+  /// Version files to handle, with their display names.
+  pub fn files(&self) -> Result<Vec<(String, Box<dyn VersionIO>)>> {
+    let detected = match self.version.detect {
+      true => <dyn VersionIO>::detect_in(Path::new(""), &self.version.headers),
+      false => Vec::new(),
+    };
+    self.merge(detected, None)
+  }
+
+  /// The `detected` files unless detection is off, followed by the sources
+  /// `component` owns (all of them without `component`), keeping the selected
+  /// kinds. A source replaces the detected file at the same path.
+  pub fn merge(
+    &self,
+    detected: Vec<(String, Box<dyn VersionIO>)>,
+    component: Option<&Component>,
+  ) -> Result<Vec<(String, Box<dyn VersionIO>)>> {
+    for prefix in &self.version.header_prefixes {
+      check_identifier(prefix, || "the header prefixes".to_string())?;
+    }
+    let sources = self.sources(component)?;
+    let mut files: Vec<(String, Box<dyn VersionIO>)> = match self.version.detect {
+      true => detected
+        .into_iter()
+        .flat_map(|(name, file)| self.with_header_prefixes(name, file))
+        .collect(),
+      false => Vec::new(),
+    };
+    files.retain(|(_, file)| {
+      !sources
+        .iter()
+        .any(|(_, source)| same_file(source.path(), file.path()))
+    });
+    files.extend(sources);
+    self.select(files)
+  }
+
+  /// The configured sources, limited to those `component` owns when given.
+  fn sources(&self, component: Option<&Component>) -> Result<Vec<(String, Box<dyn VersionIO>)>> {
+    let mut files = Vec::new();
+    for source in &self.version.sources {
+      for (name, file) in source.resolve(self)? {
+        if component.is_none_or(|component| component.owns(file.path())) {
+          files.push((name, file));
+        }
+      }
+    }
+    Ok(files)
+  }
+
+  /// `file` once per configured header prefix whose macros it defines when
+  /// it is an export header without a prefix of its own, so a header holding
+  /// none of them is left out; `file` alone otherwise, or when it cannot be
+  /// read.
+  fn with_header_prefixes(
+    &self,
+    name: String,
+    file: Box<dyn VersionIO>,
+  ) -> Vec<(String, Box<dyn VersionIO>)> {
+    if file.kind() != "export-header" || self.version.header_prefixes.is_empty() {
+      return vec![(name, file)];
+    }
+    let Ok(defined) = ExportHeaderFile::prefixes_in(file.path()) else {
+      return vec![(name, file)];
+    };
+    let prefixes: Vec<&String> = self
+      .version
+      .header_prefixes
+      .iter()
+      .filter(|prefix| defined.contains(*prefix))
+      .collect();
+    prefixes
+      .iter()
+      .map(|prefix| {
+        let name = match prefixes.len() {
+          1 => name.clone(),
+          _ => format!("{} ({})", name, prefix),
+        };
+        let file: Box<dyn VersionIO> = Box::new(ExportHeaderFile::with_prefix(file.path(), prefix));
+        (name, file)
+      })
+      .collect()
+  }
+
+  /// `files` of the kinds selected by `only` and `exclude`.
+  pub fn select(
+    &self,
+    mut files: Vec<(String, Box<dyn VersionIO>)>,
+  ) -> Result<Vec<(String, Box<dyn VersionIO>)>> {
+    for kind in self.version.only.iter().chain(&self.version.exclude) {
+      if kind_name(kind).is_none() {
+        return Err(Error::Config(format!(
+          "unknown version file kind '{}'",
+          kind
+        )));
+      }
+    }
+    let only = &self.version.only;
+    files.retain(|(_, file)| {
+      (only.is_empty() || only.iter().any(|kind| kind == file.kind()))
+        && !self.version.exclude.iter().any(|kind| kind == file.kind())
+    });
+    Ok(files)
+  }
+}
+
+impl Source {
+  /// Version files of the source, one per file its path matches, and per
+  /// header prefix of `config` for export headers without a prefix.
+  fn resolve(&self, config: &Config) -> Result<Vec<(String, Box<dyn VersionIO>)>> {
+    let kind_name = kind_name(&self.kind)
+      .ok_or_else(|| Error::Config(format!("unknown version file kind '{}'", self.kind)))?;
+    if self.pattern.is_some() && self.kind != "regex" {
+      return Err(Error::Config(format!(
+        "'{}' is not a regex source and takes no pattern",
+        self.path
+      )));
+    }
+    if self.key.is_some() && !matches!(self.kind.as_str(), "metafile" | "lua-metafile") {
+      return Err(Error::Config(format!(
+        "'{}' is not a metafile source and takes no key",
+        self.path
+      )));
+    }
+    if let Some(prefix) = &self.prefix {
+      if self.kind != "export-header" {
+        return Err(Error::Config(format!(
+          "'{}' is not an export-header source and takes no prefix",
+          self.path
+        )));
+      }
+      check_identifier(prefix, || format!("the prefix of source '{}'", self.path))?;
+    }
+    let name = self.name.as_deref().unwrap_or(kind_name);
+
+    let is_pattern = super::glob::is_pattern(&self.path);
+    let paths = match is_pattern {
+      true => super::glob::expand(Path::new(""), &self.path)
+        .into_iter()
+        .filter(|path| path.is_file())
+        .collect(),
+      false => vec![PathBuf::from(&self.path)],
+    };
+    if paths.is_empty() {
+      return Err(Error::NotFound(format!("No file matches '{}'", self.path)));
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+      let file: Box<dyn VersionIO> = match (&self.pattern, &self.key, &self.prefix) {
+        (Some(pattern), _, _) => Box::new(RegexFile::with_pattern(&path, pattern)?),
+        (_, Some(key), _) if self.kind == "lua-metafile" => {
+          Box::new(LuaMetafile::with_key(&path, key))
+        }
+        (_, Some(key), _) => Box::new(YamlMetafile::with_key(&path, key)),
+        (_, _, Some(prefix)) => Box::new(ExportHeaderFile::with_prefix(&path, prefix)),
+        _ => {
+          <dyn VersionIO>::with_kind(&self.kind, &path).expect("the kind of the source is known")
+        }
+      };
+      let name = match is_pattern {
+        true => format!(
+          "{} ({})",
+          name,
+          crate::compilers::common::display_path(&path, None)
+        ),
+        false => name.to_string(),
+      };
+      match self.prefix {
+        Some(_) => files.push((name, file)),
+        None => files.extend(config.with_header_prefixes(name, file)),
+      }
+    }
+    Ok(files)
+  }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+  let normalize = |path: &Path| path.strip_prefix(".").unwrap_or(path).to_path_buf();
+  normalize(a) == normalize(b)
+}
@@ -0,0 +1,60 @@
+use super::Bump;
+
+/// A commit message following the Conventional Commits specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConventionalCommit {
+  /// Commit type, e.g. `feat` or `fix`, in lower case.
+  pub kind: String,
+  pub scope: Option<String>,
+  /// Marked with `!` or a `BREAKING CHANGE:` footer.
+  pub breaking: bool,
+  pub description: String,
+}
+
+impl ConventionalCommit {
+  /// Parses a full commit message, returning `None` when its header is not
+  /// of the form `type(scope)!: description`.
+  pub fn parse(message: &str) -> Option<Self> {
+    let re = regex::Regex::new(r"^(\w+)(?:\(([^)]*)\))?(!)?:\s+(.+)$").ok()?;
+    let header = message.lines().next()?.trim();
+    let captures = re.captures(header)?;
+
+    let breaking_footer = message
+      .lines()
+      .skip(1)
+      .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+    Some(Self {
+      kind: captures[1].to_ascii_lowercase(),
+      scope: captures.get(2).map(|scope| scope.as_str().to_string()),
+      breaking: captures.get(3).is_some() || breaking_footer,
+      description: captures[4].to_string(),
+    })
+  }
+
+  /// Bump this commit calls for: major for breaking changes, minor for
+  /// features and patch for fixes.
+  pub fn bump(&self) -> Option<Bump> {
+    if self.breaking {
+      return Some(Bump::Major);
+    }
+    match self.kind.as_str() {
+      "feat" => Some(Bump::Minor),
+      "fix" => Some(Bump::Patch),
+      _ => None,
+    }
+  }
+}
+
+/// Largest bump called for by any of `messages`; `None` when no commit is a
+/// feature, fix or breaking change.
+pub fn bump_level<'a>(messages: impl IntoIterator<Item = &'a str>) -> Option<Bump> {
+  messages
+    .into_iter()
+    .filter_map(ConventionalCommit::parse)
+    .filter_map(|commit| commit.bump())
+    .min_by_key(|bump| match bump {
+      Bump::Major => 0,
+      Bump::Minor => 1,
+      Bump::Patch => 2,
+    })
+}
@@ -0,0 +1,14 @@
+/// Unified diff turning `old` into `new`, the content of the file at `path`.
+///
+/// Returns an empty string when the contents are equal.
+pub(crate) fn unified(path: &str, old: &str, new: &str) -> String {
+  if old == new {
+    return String::new();
+  }
+
+  similar::TextDiff::from_lines(old, new)
+    .unified_diff()
+    .context_radius(3)
+    .header(&format!("a/{}", path), &format!("b/{}", path))
+    .to_string()
+}
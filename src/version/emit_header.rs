@@ -0,0 +1,137 @@
+//! C/C++ version headers generated from scratch (`--emit-header`).
+
+#[allow(unused_imports)]
+use askama::Template;
+
+use super::report::FileStatus;
+use crate::compilers::options::{CodegenOptions, CppStandard, IncludeGuard};
+use crate::error::{Error, Result};
+use crate::git::Repository;
+use std::path::Path;
+
+/// State of the working tree recorded with `--git-info`.
+pub struct GitInfo {
+  pub commit: String,
+  /// Whether tracked files had uncommitted changes.
+  pub dirty: bool,
+}
+
+impl GitInfo {
+  /// Commit checked out in `repository` and whether it has uncommitted changes.
+  pub fn of(repository: &Repository) -> Result<Self> {
+    Ok(Self {
+      commit: repository.head()?,
+      dirty: !repository.changed_files()?.is_empty(),
+    })
+  }
+}
+
+/// What goes into a generated version header besides the version.
+pub struct HeaderOptions {
+  /// Prefix of the macros, e.g. `FOO` for `FOO_VERSION_MAJOR`.
+  pub prefix: String,
+  /// Namespace of the `constexpr` C++ struct; no struct without it.
+  pub namespace: Option<String>,
+  pub include_guard: IncludeGuard,
+  pub git: Option<GitInfo>,
+  /// Build date, as `YYYY-MM-DD`.
+  pub date: Option<String>,
+}
+
+#[derive(askama::Template)]
+#[template(path = "version.h", escape = "none")]
+struct VersionHeaderTemplate<'a> {
+  version: &'a semver::Version,
+  prefix: &'a str,
+  include_guard: Option<String>,
+  git: &'a Option<GitInfo>,
+  date: &'a Option<String>,
+  namespace_open: Option<String>,
+  namespace_close: String,
+}
+
+/// Checks that a header for `version` can be rendered with `options`.
+///
+/// The packed `<PREFIX>_VERSION` holds 8 bits of major and minor and 16 bits
+/// of patch, so larger versions are rejected.
+pub fn check(version: &semver::Version, options: &HeaderOptions) -> Result<()> {
+  crate::compilers::common::check_identifier(&options.prefix, || "--prefix".to_string())?;
+  if let Some(namespace) = &options.namespace {
+    crate::compilers::common::check_namespace(namespace)?;
+  }
+  if version.major > 0xFF || version.minor > 0xFF || version.patch > 0xFFFF {
+    return Err(Error::VersionOutOfRange {
+      version: version.clone(),
+      field: format!("{}_VERSION (0xMMmmpppp)", options.prefix),
+    });
+  }
+  Ok(())
+}
+
+/// Renders the header `file_name` for `version`, once [`check`] passes.
+pub fn render(
+  version: &semver::Version,
+  options: &HeaderOptions,
+  file_name: &str,
+) -> Result<String> {
+  check(version, options)?;
+
+  // The struct sticks to C++11, like any code including the header may.
+  let codegen = CodegenOptions {
+    standard: CppStandard::Cpp11,
+    include_guard: options.include_guard,
+    ..CodegenOptions::default()
+  };
+  let namespace = options.namespace.as_deref().unwrap_or_default();
+
+  let template = VersionHeaderTemplate {
+    version,
+    prefix: &options.prefix,
+    include_guard: codegen.guard_macro(&options.prefix, file_name),
+    git: &options.git,
+    date: &options.date,
+    namespace_open: options
+      .namespace
+      .as_ref()
+      .map(|namespace| codegen.namespace_open(namespace)),
+    namespace_close: codegen.namespace_close(namespace),
+  };
+  Ok(template.render()?)
+}
+
+/// Writes the header at `path` for `version` as requested by `args`, showing
+/// the change first when `plain`.
+pub(crate) fn write_header(
+  path: &Path,
+  version: &semver::Version,
+  options: &HeaderOptions,
+  args: &crate::args::VersionArgs,
+  plain: bool,
+) -> Result<(FileStatus, String)> {
+  let file_name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let header = render(version, options, &file_name)?;
+  let content = match std::fs::read_to_string(path) {
+    Ok(content) => content,
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(error) => return Err(Error::io(path)(error)),
+  };
+
+  if !args.dry_run
+    && let Some(parent) = path
+      .parent()
+      .filter(|parent| !parent.as_os_str().is_empty())
+  {
+    std::fs::create_dir_all(parent).map_err(Error::io(parent))?;
+  }
+  super::run::apply(
+    path,
+    &content,
+    &header,
+    args.dry_run,
+    args.interactive,
+    plain,
+  )
+}
@@ -1,8 +1,12 @@
-use anyhow::{Context, Result};
+use crate::error::{Error, Result};
+use serde::Deserialize;
 use std::path::{Path, PathBuf};
 
+/// C/C++ header with `PREFIX_VERSION_MAJOR`-like macros. A header may hold
+/// the versions of several libraries, each under its own prefix.
 pub struct ExportHeaderFile {
   path: PathBuf,
+  /// Prefix of the macros to handle; detected when there is a single one.
   macro_prefix: Option<String>,
 }
 
@@ -14,84 +18,97 @@ impl super::VersionIO for ExportHeaderFile {
     }
   }
 
-  fn new_auto() -> Result<Self> {
-    let search_dirs = ["./include", "./src", ".", "./inc", "./headers"];
-    let target_file = "version.h";
-
-    let path = find_file_recursive(&search_dirs, target_file).with_context(|| {
-      format!(
-        "Failed to find '{}' in directories: {}",
-        target_file,
-        search_dirs.join(", ")
-      )
-    })?;
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let search = HeaderSearch::default();
+    let path = Self::find_all(dir, &search)
+      .into_iter()
+      .next()
+      .ok_or_else(|| search.not_found())?;
     Ok(Self::new(path.as_path()))
   }
 
-  fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read version header: {}", self.path.display()))?;
+  fn kind(&self) -> &'static str {
+    "export-header"
+  }
 
-    let macro_prefix = self.detect_macro_prefix(&content)?;
-    self
-      .extract_version_from_content(&content, &macro_prefix)
-      .with_context(|| format!("Failed to extract version from: {}", self.path.display()))
+  fn path(&self) -> &Path {
+    &self.path
   }
 
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read version header: {}", self.path.display()))?;
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
 
     let macro_prefix = self.detect_macro_prefix(&content)?;
-    let new_content = self
-      .patch_version_in_content(&content, version, &macro_prefix)
-      .with_context(|| format!("Failed to patch version in: {}", self.path.display()))?;
-    std::fs::write(&self.path, new_content)
-      .with_context(|| format!("Failed to write version header: {}", self.path.display()))?;
-    Ok(())
+    self.extract_version_from_content(&content, &macro_prefix)
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let macro_prefix = self.detect_macro_prefix(content)?;
+    self.patch_version_in_content(content, version, &macro_prefix)
   }
 }
 
 impl ExportHeaderFile {
-  /// Auto-detect macro prefix from file content
-  fn detect_macro_prefix(&self, content: &str) -> Result<String> {
-    if let Some(prefix) = &self.macro_prefix {
-      return Ok(prefix.clone());
+  /// Handles the macros of `prefix` in the header at `path`, leaving the other
+  /// version macros alone.
+  pub fn with_prefix(path: &Path, prefix: &str) -> Self {
+    Self {
+      path: path.to_path_buf(),
+      macro_prefix: Some(prefix.to_string()),
     }
+  }
 
-    let re = regex::Regex::new(r"#\s*define\s+([A-Za-z0-9_]+)_VERSION_(MAJOR|MINOR|PATCH)\s+\d+")
-      .context("Failed to create macro detection regex")?;
+  /// Prefixes of the version macros defined in the header at `path`.
+  pub(crate) fn prefixes_in(path: &Path) -> Result<std::collections::HashSet<String>> {
+    let content = std::fs::read_to_string(path).map_err(Error::io(path))?;
+    Self::macro_prefixes(&content)
+  }
+
+  /// Prefixes of the version macros defined in `content`.
+  fn macro_prefixes(content: &str) -> Result<std::collections::HashSet<String>> {
+    let re = regex::Regex::new(
+      r#"#\s*define\s+([A-Za-z0-9_]+)_VERSION_(?:(?:MAJOR|MINOR|PATCH|TWEAK)\s+\d+|STRING\s+")"#,
+    )?;
 
     let mut candidates = std::collections::HashSet::new();
 
     for line in content.lines() {
-      if let Some(caps) = re.captures(line) {
-        if let Some(prefix_match) = caps.get(1) {
-          let prefix = prefix_match.as_str();
-          if self.is_valid_macro_prefix(prefix) {
-            candidates.insert(prefix.to_string());
-          }
+      if let Some(caps) = re.captures(line)
+        && let Some(prefix_match) = caps.get(1)
+      {
+        let prefix = prefix_match.as_str();
+        if Self::is_valid_macro_prefix(prefix) {
+          candidates.insert(prefix.to_string());
         }
       }
     }
+    Ok(candidates)
+  }
 
+  /// Auto-detect macro prefix from file content
+  fn detect_macro_prefix(&self, content: &str) -> Result<String> {
+    if let Some(prefix) = &self.macro_prefix {
+      return Ok(prefix.clone());
+    }
+
+    let candidates = Self::macro_prefixes(content)?;
     match candidates.len() {
-      0 => Err(anyhow::anyhow!(
-        "No version macros found in file. Expected patterns like: #define PREFIX_VERSION_MAJOR X"
-      )),
+      0 => Err(Error::NotFound(format!(
+        "No version macros found in {}. Expected patterns like: #define PREFIX_VERSION_MAJOR X",
+        self.path.display()
+      ))),
       1 => Ok(candidates.into_iter().next().unwrap()),
       _ => self.select_best_prefix(candidates, content),
     }
   }
 
-  /// Check if a string looks like a valid macro prefix
-  fn is_valid_macro_prefix(&self, prefix: &str) -> bool {
+  /// Check if a string looks like a valid macro prefix, in any case
+  fn is_valid_macro_prefix(prefix: &str) -> bool {
     let forbidden_prefixes = ["", "_", "__", "WIN32", "LINUX", "MACOS", "DEBUG", "RELEASE"];
 
-    !prefix.is_empty()
-      && prefix.len() >= 2
-      && !forbidden_prefixes.contains(&prefix)
-      && prefix.chars().all(|c| c.is_ascii_uppercase() || c == '_')
+    prefix.len() >= 2
+      && !forbidden_prefixes.contains(&prefix.to_ascii_uppercase().as_str())
+      && crate::compilers::common::check_identifier(prefix, String::new).is_ok()
   }
 
   /// Select the best prefix from multiple candidates
@@ -114,14 +131,27 @@ impl ExportHeaderFile {
       }
     }
 
-    prefix_counts
+    let best = prefix_counts.values().copied().max().unwrap_or(0);
+    let mut leaders: Vec<String> = prefix_counts
       .into_iter()
-      .max_by_key(|(_, count)| *count)
+      .filter(|(_, count)| *count == best)
       .map(|(prefix, _)| prefix)
-      .context("Failed to select best macro prefix from multiple candidates")
+      .collect();
+
+    if leaders.len() == 1 {
+      return Ok(leaders.remove(0));
+    }
+    leaders.sort();
+    Err(Error::AmbiguousPrefix {
+      path: self.path.clone(),
+      candidates: leaders,
+    })
   }
 
   /// Extract version from header file content using detected prefix
+  ///
+  /// A `PREFIX_VERSION_TWEAK` becomes build metadata, and a header with only
+  /// `PREFIX_VERSION_STRING` is read from the string.
   fn extract_version_from_content(
     &self,
     content: &str,
@@ -130,8 +160,17 @@ impl ExportHeaderFile {
     let mut major = None;
     let mut minor = None;
     let mut patch = None;
+    let mut tweak = None;
+    let mut prerelease = None;
+    let mut string = None;
 
     for line in content.lines() {
+      if prerelease.is_none() {
+        prerelease = self.extract_define_string(line, macro_prefix, "PRERELEASE");
+      }
+      if string.is_none() {
+        string = self.extract_define_string(line, macro_prefix, "STRING");
+      }
       if major.is_none() {
         major = self.extract_define_value(line, macro_prefix, "MAJOR");
       }
@@ -141,28 +180,45 @@ impl ExportHeaderFile {
       if patch.is_none() {
         patch = self.extract_define_value(line, macro_prefix, "PATCH");
       }
-
-      // Early exit if we found all components
-      if major.is_some() && minor.is_some() && patch.is_some() {
-        break;
+      if tweak.is_none() {
+        tweak = self.extract_define_value(line, macro_prefix, "TWEAK");
       }
     }
 
-    match (major, minor, patch) {
-      (Some(maj), Some(min), Some(pat)) => Ok(semver::Version::new(maj, min, pat)),
-      _ => Err(anyhow::anyhow!(
-        "Could not find all version components for prefix '{}'. Found: major={:?}, minor={:?}, patch={:?}",
+    match (major, minor, patch, string) {
+      (Some(maj), Some(min), Some(pat), _) => {
+        let mut version = semver::Version::new(maj, min, pat);
+        if let Some(pre) = prerelease.filter(|pre| !pre.is_empty()) {
+          version.pre = semver::Prerelease::new(&pre).map_err(|source| Error::InvalidVersion {
+            version: pre,
+            source,
+          })?;
+        }
+        if let Some(tweak) = tweak {
+          version.build =
+            semver::BuildMetadata::new(&tweak.to_string()).expect("a number is valid metadata");
+        }
+        Ok(version)
+      }
+      (None, None, None, Some(string)) => super::parse_version(&string),
+      _ => Err(Error::NotFound(format!(
+        "Could not find all version components for prefix '{}' in {}. Found: major={:?}, minor={:?}, patch={:?}",
         macro_prefix,
+        self.path.display(),
         major,
         minor,
         patch
-      )),
+      ))),
     }
   }
 
   /// Extract a specific define value from a line
   fn extract_define_value(&self, line: &str, macro_prefix: &str, suffix: &str) -> Option<u64> {
-    let pattern = format!(r"#\s*define\s+{}_VERSION_{}\s+(\d+)", macro_prefix, suffix);
+    let pattern = format!(
+      r"#\s*define\s+{}_VERSION_{}\s+(\d+)",
+      regex::escape(macro_prefix),
+      suffix
+    );
     let re = regex::Regex::new(&pattern).ok()?;
 
     re.captures(line)
@@ -170,103 +226,198 @@ impl ExportHeaderFile {
       .and_then(|m| m.as_str().parse().ok())
   }
 
+  /// Extract the string of a `PREFIX_VERSION_<suffix> "..."` line
+  fn extract_define_string(&self, line: &str, macro_prefix: &str, suffix: &str) -> Option<String> {
+    let pattern = format!(
+      r#"#\s*define\s+{}_VERSION_{}\s+"([^"]*)""#,
+      regex::escape(macro_prefix),
+      suffix
+    );
+    let re = regex::Regex::new(&pattern).ok()?;
+
+    re.captures(line)
+      .and_then(|caps| caps.get(1))
+      .map(|m| m.as_str().to_string())
+  }
+
   /// Patch version in header file content
+  ///
+  /// Only the macro values are replaced, keeping the spacing and line endings
+  /// of the file. A `PREFIX_VERSION_PRERELEASE` macro is kept up to date when
+  /// present, and added after the patch macro when a pre-release version is
+  /// written.
   fn patch_version_in_content(
     &self,
     content: &str,
     version: &semver::Version,
     macro_prefix: &str,
   ) -> Result<String> {
-    let mut result = String::new();
-
-    for line in content.lines() {
-      let patched_line = self.patch_version_in_line(line, version, macro_prefix);
-      result.push_str(&patched_line);
-      result.push('\n');
-    }
-
-    Ok(result)
-  }
-
-  /// Patch version in a single line
-  fn patch_version_in_line(
-    &self,
-    line: &str,
-    version: &semver::Version,
-    macro_prefix: &str,
-  ) -> String {
-    let mut result = line.to_string();
-
-    let major_pattern = format!(r"#\s*define\s+{}_VERSION_MAJOR\s+\d+", macro_prefix);
-    let major_replacement = format!("#define {}_VERSION_MAJOR {}", macro_prefix, version.major);
-    if let Ok(re) = regex::Regex::new(&major_pattern) {
-      result = re.replace(&result, &major_replacement).to_string();
+    let tweak = version.build.as_str().parse::<u64>().unwrap_or(0);
+    let number = r"(\d+)";
+    let string = r#""([^"\n]*)""#;
+    let values = [
+      ("MAJOR", number, version.major.to_string()),
+      ("MINOR", number, version.minor.to_string()),
+      ("PATCH", number, version.patch.to_string()),
+      ("TWEAK", number, tweak.to_string()),
+      ("STRING", string, version.to_string()),
+      ("PRERELEASE", string, version.pre.to_string()),
+    ];
+
+    let mut edits = Vec::new();
+    let mut patch_end = None;
+    let mut has_prerelease = false;
+    for (suffix, value_pattern, value) in values {
+      let re = regex::Regex::new(&format!(
+        r"#[ \t]*define[ \t]+{}_VERSION_{}[ \t]+{}",
+        regex::escape(macro_prefix),
+        suffix,
+        value_pattern
+      ))?;
+      for captures in re.captures_iter(content) {
+        match suffix {
+          "PATCH" => patch_end = patch_end.or(captures.get(0).map(|define| define.end())),
+          "PRERELEASE" => has_prerelease = true,
+          _ => {}
+        }
+        let span = captures.get(1).expect("the value is captured").range();
+        edits.push((span, value.clone()));
+      }
     }
 
-    let minor_pattern = format!(r"#\s*define\s+{}_VERSION_MINOR\s+\d+", macro_prefix);
-    let minor_replacement = format!("#define {}_VERSION_MINOR {}", macro_prefix, version.minor);
-    if let Ok(re) = regex::Regex::new(&minor_pattern) {
-      result = re.replace(&result, &minor_replacement).to_string();
+    if !has_prerelease
+      && !version.pre.is_empty()
+      && let Some(end) = patch_end
+    {
+      let line = format!(
+        "#define {}_VERSION_PRERELEASE \"{}\"",
+        macro_prefix, version.pre
+      );
+      let insertion = match content[end..].find('\n') {
+        Some(newline) => {
+          let eol = if content[..end + newline].ends_with('\r') {
+            "\r\n"
+          } else {
+            "\n"
+          };
+          (end + newline + 1, format!("{}{}", line, eol))
+        }
+        None => (content.len(), format!("\n{}", line)),
+      };
+      edits.push((insertion.0..insertion.0, insertion.1));
     }
 
-    let patch_pattern = format!(r"#\s*define\s+{}_VERSION_PATCH\s+\d+", macro_prefix);
-    let patch_replacement = format!("#define {}_VERSION_PATCH {}", macro_prefix, version.patch);
-    if let Ok(re) = regex::Regex::new(&patch_pattern) {
-      result = re.replace(&result, &patch_replacement).to_string();
+    edits.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+    let mut patched = content.to_string();
+    for (span, value) in edits {
+      patched.replace_range(span, &value);
     }
-
-    result
+    Ok(patched)
   }
 }
 
-/// Recursively searches for a file in the given directories with error handling
-fn find_file_recursive(dirs: &[&str], filename: &str) -> Result<PathBuf> {
-  for dir in dirs {
-    if let Some(path) = search_directory(Path::new(dir), filename)? {
-      return Ok(path);
-    }
-  }
-
-  Err(anyhow::anyhow!(
-    "File '{}' not found in any of the specified directories",
-    filename
-  ))
+/// Where to look for export headers below a directory.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeaderSearch {
+  /// File names to look for, where `*` and `?` are wildcards.
+  pub names: Vec<String>,
+  /// Directories searched in turn, with everything below them.
+  pub dirs: Vec<String>,
+  /// Whether every matching header is a version file, rather than the first.
+  pub all: bool,
 }
 
-/// Recursively searches a directory for a file with proper error handling
-fn search_directory(dir: &Path, filename: &str) -> Result<Option<PathBuf>> {
-  if !dir.exists() {
-    return Ok(None);
+impl Default for HeaderSearch {
+  fn default() -> Self {
+    Self {
+      names: vec!["version.h".to_string()],
+      dirs: ["include", "src", ".", "inc", "headers"]
+        .map(str::to_string)
+        .to_vec(),
+      all: false,
+    }
   }
+}
 
-  if !dir.is_dir() {
-    return Ok(None);
+impl HeaderSearch {
+  fn not_found(&self) -> Error {
+    Error::NotFound(format!(
+      "Failed to find '{}' in directories: {}",
+      self.names.join("', '"),
+      self.dirs.join(", ")
+    ))
   }
+}
 
-  let entries = std::fs::read_dir(dir)
-    .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
-
-  for entry in entries {
-    let entry = entry.with_context(|| format!("Failed to read entry in: {}", dir.display()))?;
-    let path = entry.path();
+impl ExportHeaderFile {
+  /// Headers below `root` matching `search`, in the order of its directories
+  /// and sorted by path within each, skipping what [`walk`] skips.
+  pub fn find_all(root: &Path, search: &HeaderSearch) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    for dir in &search.dirs {
+      let dir = root.join(dir.trim_start_matches("./"));
+      if !dir.is_dir() {
+        continue;
+      }
 
-    if path.is_file() {
-      if let Some(name) = path.file_name() {
-        if name == filename {
-          return Ok(Some(path));
+      for entry in walk(&dir).build().filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let matches = entry.file_type().is_some_and(|kind| kind.is_file())
+          && is_candidate(search, &entry.file_name().to_string_lossy());
+        // Paths below the current directory are shown without `./`.
+        let path = path.strip_prefix(".").unwrap_or(path);
+        if matches && !found.iter().any(|other| other == path) {
+          found.push(path.to_path_buf());
         }
       }
-    } else if path.is_dir() {
-      let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-      if !should_skip_directory(dir_name) {
-        if let Some(found) = search_directory(&path, filename)? {
-          return Ok(Some(found));
-        }
+    }
+    found
+  }
+}
+
+/// The `candidates`, files named like headers that one walk found, that
+/// [`ExportHeaderFile::find_all`] would find below `root`, in the same order.
+pub(super) fn find_among(
+  root: &Path,
+  search: &HeaderSearch,
+  candidates: &[PathBuf],
+) -> Vec<PathBuf> {
+  let mut found: Vec<PathBuf> = Vec::new();
+  for dir in &search.dirs {
+    let dir = root.join(dir.trim_start_matches("./"));
+    let dir = dir.strip_prefix(".").unwrap_or(&dir);
+    for path in candidates {
+      if super::component::within(path, dir) && !found.contains(path) {
+        found.push(path.clone());
       }
     }
   }
+  found
+}
+
+/// Whether `file_name` is one of the header names of `search`.
+pub(super) fn is_candidate(search: &HeaderSearch, file_name: &str) -> bool {
+  search
+    .names
+    .iter()
+    .any(|name| super::glob::wildcard(name, file_name))
+}
 
-  Ok(None)
+/// Walk of `dir` sorted by file name, leaving out files ignored by
+/// `.gitignore` and similar files, hidden ones, and the directories
+/// [`should_skip_directory`] names even when nothing ignores them.
+pub(super) fn walk(dir: &Path) -> ignore::WalkBuilder {
+  let mut builder = ignore::WalkBuilder::new(dir);
+  builder
+    .require_git(false)
+    .sort_by_file_name(|a, b| a.cmp(b))
+    .filter_entry(|entry| {
+      entry.depth() == 0
+        || !entry.file_type().is_some_and(|kind| kind.is_dir())
+        || !should_skip_directory(&entry.file_name().to_string_lossy())
+    });
+  builder
 }
 
 /// Skip common build and cache directories to improve performance
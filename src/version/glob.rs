@@ -0,0 +1,61 @@
+//! Minimal path globs, as used by Cargo's `members` and version sources.
+
+use std::path::{Path, PathBuf};
+
+/// Existing paths matching the `/`-separated `pattern` below `base`, sorted
+/// per wildcard; `*` and `?` match within a single path component.
+pub(super) fn expand(base: &Path, pattern: &str) -> Vec<PathBuf> {
+  let mut paths = vec![base.to_path_buf()];
+  for component in pattern
+    .split('/')
+    .filter(|component| !component.is_empty() && *component != ".")
+  {
+    if !is_pattern(component) {
+      paths = paths.into_iter().map(|path| path.join(component)).collect();
+      continue;
+    }
+    paths = paths
+      .into_iter()
+      .flat_map(|path| {
+        let listing = match path.as_os_str().is_empty() {
+          true => Path::new("."),
+          false => path.as_path(),
+        };
+        let mut matches: Vec<PathBuf> = std::fs::read_dir(listing)
+          .into_iter()
+          .flatten()
+          .filter_map(|entry| entry.ok())
+          .filter(|entry| wildcard(component, &entry.file_name().to_string_lossy()))
+          .map(|entry| path.join(entry.file_name()))
+          .collect();
+        matches.sort();
+        matches
+      })
+      .collect();
+  }
+  paths.into_iter().filter(|path| path.exists()).collect()
+}
+
+/// Whether `pattern` contains a wildcard.
+pub(super) fn is_pattern(pattern: &str) -> bool {
+  pattern.contains(['*', '?'])
+}
+
+/// Whether `name` matches `pattern`, where `*` and `?` are wildcards.
+pub(super) fn wildcard(pattern: &str, name: &str) -> bool {
+  match pattern.chars().next() {
+    None => name.is_empty(),
+    Some('*') => (0..=name.len())
+      .filter(|index| name.is_char_boundary(*index))
+      .any(|index| wildcard(&pattern[1..], &name[index..])),
+    Some(char) => {
+      let mut chars = name.chars();
+      match chars.next() {
+        Some(first) if char == '?' || char == first => {
+          wildcard(&pattern[char.len_utf8()..], chars.as_str())
+        }
+        _ => false,
+      }
+    }
+  }
+}
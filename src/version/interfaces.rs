@@ -1,37 +1,199 @@
-use anyhow::Result;
-use std::path::Path;
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
 
+/// Parses `version` as semver, reporting the offending string on failure.
+pub(crate) fn parse_version(version: &str) -> Result<semver::Version> {
+  semver::Version::parse(version).map_err(|source| Error::InvalidVersion {
+    version: version.to_string(),
+    source,
+  })
+}
+
+/// A kind of version file.
+pub struct Kind {
+  /// Stable identifier, e.g. `cargo` or `export-header`.
+  pub id: &'static str,
+  /// Display name of its files.
+  pub name: &'static str,
+  new: fn(&Path) -> Box<dyn VersionIO>,
+  new_auto_in: fn(&Path) -> Result<Box<dyn VersionIO>>,
+}
+
+impl Kind {
+  const fn of<T: VersionIO + 'static>(id: &'static str, name: &'static str) -> Self {
+    Self {
+      id,
+      name,
+      new: |path| Box::new(T::new(path)),
+      new_auto_in: |dir| Ok(Box::new(T::new_auto_in(dir)?)),
+    }
+  }
+}
+
+/// Kinds of version files, in detection order.
+pub const KINDS: [Kind; 15] = {
+  use super::*;
+  [
+    Kind::of::<YamlMetafile>("metafile", "Plugin metafile"),
+    Kind::of::<LuaMetafile>("lua-metafile", "Plugin metafile (lua)"),
+    Kind::of::<CargoFile>("cargo", "Cargo manifest"),
+    Kind::of::<CargoLockFile>("cargo-lock", "Cargo lockfile"),
+    Kind::of::<CmakeFile>("cmake", "CMakeLists"),
+    Kind::of::<ConanFile>("conan", "Conanfile"),
+    Kind::of::<ConanDataFile>("conandata", "Conan data"),
+    Kind::of::<MesonFile>("meson", "Meson project"),
+    Kind::of::<VcpkgFile>("vcpkg", "vcpkg manifest"),
+    Kind::of::<PackageJsonFile>("npm", "npm package"),
+    Kind::of::<PackageLockFile>("npm-lock", "npm lockfile"),
+    Kind::of::<PyprojectFile>("pyproject", "Python project"),
+    Kind::of::<SetupCfgFile>("setup-cfg", "setuptools config"),
+    Kind::of::<ExportHeaderFile>("export-header", "Export header"),
+    Kind::of::<RegexFile>("regex", "Pattern"),
+  ]
+};
+
+/// Display name of the version files of `kind`.
+pub fn kind_name(kind: &str) -> Option<&'static str> {
+  KINDS
+    .iter()
+    .find(|known| known.id == kind)
+    .map(|known| known.name)
+}
+
+/// A file that stores the project version.
 pub trait VersionIO {
+  /// Handles the version file at `path`.
   fn new(path: &Path) -> Self
   where
     Self: Sized;
-  fn new_auto() -> Result<Self>
+  /// Looks for the version file in its conventional locations under `dir`.
+  fn new_auto_in(dir: &Path) -> Result<Self>
   where
     Self: Sized;
+  /// Looks for the version file in its conventional locations under the current directory.
+  fn new_auto() -> Result<Self>
+  where
+    Self: Sized,
+  {
+    Self::new_auto_in(Path::new(""))
+  }
+  /// Stable identifier of the file format, e.g. `cargo` or `export-header`.
+  fn kind(&self) -> &'static str;
+  /// Location of the version file.
+  fn path(&self) -> &Path;
+  /// Whether the format can store pre-release identifiers; only the numeric
+  /// core of the version is kept otherwise.
+  fn supports_prerelease(&self) -> bool {
+    true
+  }
   fn read(&self) -> Result<semver::Version>;
-  fn write(&self, version: &semver::Version) -> Result<()>;
+  /// Returns `content` of the file with its version replaced by `version`.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String>;
+
+  /// Reads the file and returns its current content along with the content
+  /// [`VersionIO::write`] would leave behind, without touching the file.
+  fn preview(&self, version: &semver::Version) -> Result<(String, String)> {
+    let content = std::fs::read_to_string(self.path()).map_err(Error::io(self.path()))?;
+    let patched = self.patch(&content, version)?;
+    Ok((content, patched))
+  }
+
+  /// Stores `version` in the file; the file is left untouched when nothing changes.
+  fn write(&self, version: &semver::Version) -> Result<()> {
+    let (content, patched) = self.preview(version)?;
+    if patched != content {
+      std::fs::write(self.path(), patched).map_err(Error::io(self.path()))?;
+    }
+    Ok(())
+  }
 }
 
 impl dyn VersionIO {
-  pub fn all() -> Vec<(&'static str, Box<dyn VersionIO>)> {
+  /// Handles the file at `path` as a version file of `kind`.
+  pub fn with_kind(kind: &str, path: &Path) -> Option<Box<dyn VersionIO>> {
+    KINDS
+      .iter()
+      .find(|known| known.id == kind)
+      .map(|known| (known.new)(path))
+  }
+
+  /// Every version file detected in the current directory, with its display name.
+  pub fn all() -> Vec<(String, Box<dyn VersionIO>)> {
+    Self::all_in(Path::new(""))
+  }
+
+  /// Every version file detected in `dir`, with its display name.
+  pub fn all_in(dir: &Path) -> Vec<(String, Box<dyn VersionIO>)> {
+    Self::detect_in(dir, &super::HeaderSearch::default())
+  }
+
+  /// Every version file detected in `dir`, looking for export headers as
+  /// `headers` says.
+  pub fn detect_in(dir: &Path, headers: &super::HeaderSearch) -> Vec<(String, Box<dyn VersionIO>)> {
+    let header_paths = super::ExportHeaderFile::find_all(dir, headers);
+    Self::detect_with_headers(dir, header_paths, headers.all)
+  }
+
+  /// Every version file detected in `dir`, where `header_paths` are the export
+  /// headers found for it, of which only the first is kept unless `all`.
+  pub(super) fn detect_with_headers(
+    dir: &Path,
+    mut header_paths: Vec<PathBuf>,
+    all: bool,
+  ) -> Vec<(String, Box<dyn VersionIO>)> {
+    // The manifests and the lockfile share one read of the workspace.
+    let workspace = super::cargo::Workspace::discover_in(dir).ok();
     let mut files = Vec::new();
-    if let Ok(meta) = super::YamlMetafile::new_auto() {
-      files.push(("Plugin metafile", Box::new(meta) as Box<dyn VersionIO>));
-    }
-    if let Ok(lua) = super::LuaMetafile::new_auto() {
-      files.push(("Plugin metafile (lua)", Box::new(lua) as Box<dyn VersionIO>));
-    }
-    if let Ok(cargo) = super::CargoFile::new_auto() {
-      files.push(("Cargo manifest", Box::new(cargo) as Box<dyn VersionIO>));
-    }
-    if let Ok(cmake) = super::CmakeFile::new_auto() {
-      files.push(("CMakeLists", Box::new(cmake) as Box<dyn VersionIO>));
-    }
-    if let Ok(conan) = super::ConanFile::new_auto() {
-      files.push(("Conanfile", Box::new(conan) as Box<dyn VersionIO>));
-    }
-    if let Ok(header) = super::ExportHeaderFile::new_auto() {
-      files.push(("Export header", Box::new(header) as Box<dyn VersionIO>));
+    for kind in &KINDS {
+      match kind.id {
+        "cargo" => {
+          if let Ok(cargo) = super::CargoFile::in_workspace(dir, workspace.as_ref()) {
+            files.push((kind.name.to_string(), Box::new(cargo) as Box<dyn VersionIO>));
+          }
+          for (name, member) in workspace.iter().flat_map(super::CargoFile::members_of) {
+            files.push((
+              format!("{} ({})", kind.name, name),
+              Box::new(member) as Box<dyn VersionIO>,
+            ));
+          }
+        }
+        "cargo-lock" => {
+          let lockfile = workspace
+            .as_ref()
+            .and_then(|workspace| super::CargoLockFile::in_workspace(dir, workspace).ok());
+          if let Some(lockfile) = lockfile {
+            files.push((
+              kind.name.to_string(),
+              Box::new(lockfile) as Box<dyn VersionIO>,
+            ));
+          }
+        }
+        "export-header" => {
+          header_paths.truncate(match all {
+            true => header_paths.len(),
+            false => 1,
+          });
+          for path in &header_paths {
+            let name = match header_paths.len() {
+              1 => kind.name.to_string(),
+              _ => format!(
+                "{} ({})",
+                kind.name,
+                crate::compilers::common::display_path(path, None)
+              ),
+            };
+            files.push((
+              name,
+              Box::new(super::ExportHeaderFile::new(path)) as Box<dyn VersionIO>,
+            ));
+          }
+        }
+        _ => {
+          if let Ok(file) = (kind.new_auto_in)(dir) {
+            files.push((kind.name.to_string(), file));
+          }
+        }
+      }
     }
 
     files
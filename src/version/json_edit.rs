@@ -0,0 +1,141 @@
+//! In-place edits of JSON documents, leaving everything but the edited values
+//! byte for byte as it was.
+
+use std::ops::Range;
+
+/// Byte range of the string at the object keys `keys` in the JSON `content`,
+/// which must be well-formed, without its quotes; none when the value there is
+/// not a string.
+pub(super) fn string_span(content: &str, keys: &[&str]) -> Option<Range<usize>> {
+  Scanner::new(content).find(keys)
+}
+
+/// Byte range of the number at the object keys `keys` in the JSON `content`,
+/// which must be well-formed.
+pub(super) fn number_span(content: &str, keys: &[&str]) -> Option<Range<usize>> {
+  let mut scanner = Scanner::new(content);
+  match scanner.seek(keys)? {
+    b'-' | b'0'..=b'9' => {
+      let start = scanner.pos;
+      scanner.skip()?;
+      Some(start..scanner.pos)
+    }
+    _ => None,
+  }
+}
+
+/// Locates values in a JSON document already known to be well-formed.
+struct Scanner<'a> {
+  text: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+  fn new(content: &'a str) -> Self {
+    Self {
+      text: content.as_bytes(),
+      pos: 0,
+    }
+  }
+
+  /// Byte range of the string at `keys`, without its quotes.
+  fn find(&mut self, keys: &[&str]) -> Option<Range<usize>> {
+    match self.seek(keys)? {
+      b'"' => self.string(),
+      _ => None,
+    }
+  }
+
+  /// Moves to the value at `keys`, returning its first byte.
+  fn seek(&mut self, keys: &[&str]) -> Option<u8> {
+    self.whitespace();
+    let Some((key, rest)) = keys.split_first() else {
+      return self.peek();
+    };
+
+    self.expect(b'{')?;
+    loop {
+      self.whitespace();
+      if self.peek()? == b'}' {
+        return None;
+      }
+      let name = self.string()?;
+      self.whitespace();
+      self.expect(b':')?;
+      if &self.text[name] == key.as_bytes() {
+        return self.seek(rest);
+      }
+      self.skip()?;
+      self.whitespace();
+      self.expect(b',')?;
+    }
+  }
+
+  /// Moves past the value at the current position.
+  fn skip(&mut self) -> Option<()> {
+    self.whitespace();
+    match self.peek()? {
+      b'"' => {
+        self.string()?;
+      }
+      b'{' | b'[' => {
+        let mut depth = 0;
+        loop {
+          match self.peek()? {
+            b'"' => {
+              self.string()?;
+              continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            _ => {}
+          }
+          self.pos += 1;
+          if depth == 0 {
+            break;
+          }
+        }
+      }
+      _ => {
+        while !matches!(self.peek()?, b',' | b'}' | b']') && !self.peek()?.is_ascii_whitespace() {
+          self.pos += 1;
+        }
+      }
+    }
+    Some(())
+  }
+
+  /// Moves past the string at the current position, returning its contents.
+  fn string(&mut self) -> Option<Range<usize>> {
+    self.expect(b'"')?;
+    let start = self.pos;
+    loop {
+      match self.peek()? {
+        b'\\' => self.pos += 2,
+        b'"' => break,
+        _ => self.pos += 1,
+      }
+    }
+    let end = self.pos;
+    self.pos += 1;
+    Some(start..end)
+  }
+
+  fn whitespace(&mut self) {
+    while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+      self.pos += 1;
+    }
+  }
+
+  fn expect(&mut self, byte: u8) -> Option<()> {
+    if self.peek()? != byte {
+      return None;
+    }
+    self.pos += 1;
+    Some(())
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.text.get(self.pos).copied()
+  }
+}
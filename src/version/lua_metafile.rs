@@ -1,81 +1,300 @@
-use anyhow::{Context, Result};
-use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
 
+/// Plugin metafile written in Lua, like `plugin = { version = "1.2.3" }` or
+/// `return { meta = { version = '1.2.3' } }`.
 pub struct LuaMetafile {
   path: PathBuf,
+  /// Keys leading to the version from the top level or from the plugin
+  /// table, outermost first.
+  key: Vec<String>,
 }
 
-impl super::VersionIO for LuaMetafile {
-  fn new(path: &Path) -> Self {
+impl LuaMetafile {
+  /// Handles the metafile at `path` with the version at the dotted `key`.
+  pub fn with_key(path: &Path, key: &str) -> Self {
     Self {
       path: path.to_path_buf(),
+      key: key.split('.').map(str::to_string).collect(),
     }
   }
 
-  fn new_auto() -> Result<Self> {
+  /// Span of the contents of the version string: the first field at the key,
+  /// either from the top level or from the plugin table. The plugin table is
+  /// the one the chunk returns, or any table assigned at the top level when
+  /// it returns none, so the versions of nested tables like dependencies are
+  /// never taken for the plugin's own.
+  fn version_span(&self, content: &str) -> Result<Range<usize>> {
+    let chunk = Chunk::parse(content);
+    let plugin_tables = match chunk.returned {
+      Some(returned) => vec![returned],
+      None => chunk.tables,
+    };
+    chunk
+      .fields
+      .into_iter()
+      .find(|(path, _)| {
+        *path == self.key
+          || plugin_tables
+            .iter()
+            .any(|table| path.starts_with(table) && path[table.len()..] == self.key)
+      })
+      .map(|(_, span)| span)
+      .ok_or_else(|| {
+        Error::NotFound(match self.key.as_slice() {
+          [key] if key == "version" => "Version field not found in meta.lua".to_string(),
+          key => format!("Version field '{}' not found in meta.lua", key.join(".")),
+        })
+      })
+  }
+}
+
+impl super::VersionIO for LuaMetafile {
+  fn new(path: &Path) -> Self {
+    Self::with_key(path, "version")
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
     let path_candidates = vec!["meta.lua", "meta/meta.lua"];
     let mut path = None;
     for path_candidate in path_candidates {
-      let path_candidate = PathBuf::from(path_candidate);
+      let path_candidate = dir.join(path_candidate);
       if path_candidate.exists() {
         path = Some(path_candidate);
         break;
       }
     }
     Ok(Self::new(
-      path.context("Failed to find meta.lua file")?.as_path(),
+      path
+        .ok_or_else(|| Error::NotFound("meta.lua not found".to_string()))?
+        .as_path(),
     ))
   }
 
-  fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read meta.lua: {}", self.path.display()))?;
+  fn kind(&self) -> &'static str {
+    "lua-metafile"
+  }
 
-    let re = regex::Regex::new(r#"(?m)^\s*version\s*=\s*"([^"]+)",\s*$"#)
-      .context("Failed to compile regex")?;
+  fn path(&self) -> &Path {
+    &self.path
+  }
 
-    for line in content.lines() {
-      if let Some(captures) = re.captures(line) {
-        let version_str = captures.get(1).unwrap().as_str();
-        return semver::Version::parse(version_str)
-          .with_context(|| format!("Failed to parse version: {}", version_str));
-      }
-    }
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let span = self.version_span(&content)?;
+    super::parse_version(&content[span])
+  }
 
-    Err(anyhow::anyhow!("Version field not found in meta.lua"))
+  /// Replaces the contents of the version string only, whatever its quotes.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let span = self.version_span(content)?;
+    let mut patched = content.to_string();
+    patched.replace_range(span, &version.to_string());
+    Ok(patched)
   }
+}
 
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)
-      .with_context(|| format!("Failed to read meta.lua: {}", self.path.display()))?;
+#[derive(PartialEq, Eq)]
+enum Token<'a> {
+  Name(&'a str),
+  /// A string, given by the span of its contents.
+  String(Range<usize>),
+  Op(&'a str),
+  /// Numbers and anything else of no interest.
+  Other,
+}
 
-    let re = regex::Regex::new(r#"(?m)^(\s*version\s*=\s*")[^"]+(",\s*)$"#)
-      .context("Failed to compile regex")?;
+/// Segment standing for the table a chunk returns in the paths of its fields.
+const RETURNED: &str = "return";
 
-    let mut new_lines = Vec::new();
-    let mut version_replaced = false;
+/// What the version is looked up in: the string fields and variables of a Lua
+/// chunk with their path, i.e. the keys of the enclosing tables and the field
+/// or dotted variable name, along with the tables it declares.
+struct Chunk {
+  fields: Vec<(Vec<String>, Range<usize>)>,
+  /// Paths of the tables assigned at the top level.
+  tables: Vec<Vec<String>>,
+  /// Path of the returned table: [`RETURNED`] when it is written out, or the
+  /// name of the variable holding it.
+  returned: Option<Vec<String>>,
+}
 
-    for line in content.lines() {
-      let current_line = line;
+impl Chunk {
+  fn parse(content: &str) -> Self {
+    let tokens = tokens(content);
+    let mut chunk = Self {
+      fields: Vec::new(),
+      tables: Vec::new(),
+      returned: None,
+    };
+    // Paths of the open tables; anonymous ones, like array items, add a `[]`
+    // segment.
+    let mut open: Vec<Vec<String>> = Vec::new();
+    let mut target: Option<Vec<String>> = None;
 
-      if !version_replaced {
-        if let Some(captures) = re.captures(current_line) {
-          let new_line = format!("{}{}{}", &captures[1], version, &captures[2]);
-          new_lines.push(new_line);
-          version_replaced = true;
-          continue;
+    for (index, token) in tokens.iter().enumerate() {
+      match token {
+        Token::Op("=") => {
+          let path = assignment_target(content, &tokens[..index]);
+          match tokens.get(index + 1) {
+            Some(Token::String(span)) => {
+              if let Some(path) = path {
+                let mut path_in_tables = open.concat();
+                path_in_tables.extend(path);
+                chunk.fields.push((path_in_tables, span.clone()));
+              }
+            }
+            Some(Token::Op("{")) => {
+              if open.is_empty()
+                && let Some(path) = &path
+              {
+                chunk.tables.push(path.clone());
+              }
+              target = path;
+            }
+            _ => {}
+          }
+        }
+        Token::Name("return") if open.is_empty() => match tokens.get(index + 1) {
+          Some(Token::Op("{")) => target = Some(vec![RETURNED.to_string()]),
+          Some(Token::Name(name)) => chunk.returned = Some(vec![name.to_string()]),
+          _ => {}
+        },
+        Token::Op("{") => {
+          let path = target.take().unwrap_or_else(|| vec!["[]".to_string()]);
+          if open.is_empty() && path == [RETURNED] {
+            chunk.returned = Some(path.clone());
+          }
+          open.push(path);
+        }
+        Token::Op("}") => {
+          open.pop();
         }
+        _ => {}
       }
-
-      new_lines.push(current_line.to_string());
     }
+    chunk
+  }
+}
 
-    let new_content = new_lines.join("\n");
-    if new_content != content {
-      std::fs::write(&self.path, new_content)
-        .with_context(|| format!("Failed to write meta.lua: {}", self.path.display()))?;
+/// Path assigned by the `=` following `tokens`: `name`, `a.b.name` or
+/// `["name"]`.
+fn assignment_target(content: &str, tokens: &[Token]) -> Option<Vec<String>> {
+  match tokens {
+    [.., Token::Op("["), Token::String(span), Token::Op("]")] => {
+      Some(vec![content[span.clone()].to_string()])
+    }
+    [.., Token::Name(_)] => {
+      let mut path = Vec::new();
+      let mut rest = tokens;
+      while let [before @ .., Token::Name(name)] = rest {
+        path.insert(0, name.to_string());
+        match before {
+          [before @ .., Token::Op(".")] => rest = before,
+          _ => break,
+        }
+      }
+      Some(path)
     }
+    _ => None,
+  }
+}
 
-    Ok(())
+/// Tokens of a Lua chunk, without comments.
+fn tokens(content: &str) -> Vec<Token<'_>> {
+  let bytes = content.as_bytes();
+  let mut tokens = Vec::new();
+  let mut pos = 0;
+
+  while let Some(&byte) = bytes.get(pos) {
+    let start = pos;
+    match byte {
+      _ if byte.is_ascii_whitespace() => {
+        pos += 1;
+        continue;
+      }
+      b'-' if content[pos..].starts_with("--") => {
+        pos += 2;
+        match long_bracket(content, pos) {
+          Some((_, end)) => pos = end,
+          None => {
+            pos = content[pos..]
+              .find('\n')
+              .map_or(content.len(), |end| pos + end)
+          }
+        }
+        continue;
+      }
+      b'"' | b'\'' => {
+        pos += 1;
+        while let Some(&next) = bytes.get(pos) {
+          match next {
+            b'\\' => pos += 2,
+            b'\n' => break,
+            _ if next == byte => break,
+            _ => pos += 1,
+          }
+        }
+        let end = pos.min(content.len());
+        pos = end + 1;
+        tokens.push(Token::String(start + 1..end));
+      }
+      b'[' if long_bracket(content, pos).is_some() => {
+        let (contents, end) = long_bracket(content, pos).expect("just matched");
+        pos = end;
+        tokens.push(Token::String(contents));
+      }
+      _ if byte.is_ascii_alphabetic() || byte == b'_' => {
+        while bytes
+          .get(pos)
+          .is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+        {
+          pos += 1;
+        }
+        tokens.push(Token::Name(&content[start..pos]));
+      }
+      _ if byte.is_ascii_digit() => {
+        while bytes
+          .get(pos)
+          .is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'.')
+        {
+          pos += 1;
+        }
+        tokens.push(Token::Other);
+      }
+      _ => {
+        let length = ["...", "..", "==", "~=", "<=", ">=", "::", "//", "<<", ">>"]
+          .iter()
+          .find(|op| content[pos..].starts_with(**op))
+          .map_or(
+            content[pos..].chars().next().map_or(1, char::len_utf8),
+            |op| op.len(),
+          );
+        pos += length;
+        tokens.push(Token::Op(&content[start..pos]));
+      }
+    }
   }
-}
\ No newline at end of file
+  tokens
+}
+
+/// Contents and end of the long string or comment `[==[ ... ]==]` at `pos`;
+/// a newline right after the opening bracket is not part of the contents.
+fn long_bracket(content: &str, pos: usize) -> Option<(Range<usize>, usize)> {
+  let rest = content[pos..].strip_prefix('[')?;
+  let equals = rest.len() - rest.trim_start_matches('=').len();
+  let rest = rest[equals..].strip_prefix('[')?;
+  let newline = match rest.starts_with("\r\n") {
+    true => 2,
+    false => usize::from(rest.starts_with('\n')),
+  };
+  let start = pos + equals + 2 + newline;
+  let close = format!("]{}]", "=".repeat(equals));
+  let end = content[start..]
+    .find(&close)
+    .map_or(content.len(), |end| start + end);
+  Some((start..end, (end + close.len()).min(content.len())))
+}
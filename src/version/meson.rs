@@ -0,0 +1,98 @@
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+pub struct MesonFile {
+  path: PathBuf,
+}
+
+impl super::VersionIO for MesonFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("meson.build");
+    if !path.exists() {
+      return Err(Error::NotFound("meson.build not found".to_string()));
+    }
+    Ok(Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "meson"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let span = version_span(&content)?;
+    super::parse_version(&content[span])
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let span = version_span(content)?;
+    let mut patched = content.to_string();
+    patched.replace_range(span, &version.to_string());
+    Ok(patched)
+  }
+}
+
+/// Byte range of the `version:` string of the `project()` call, without its
+/// quotes. The call may span several lines.
+fn version_span(content: &str) -> Result<Range<usize>> {
+  let not_found =
+    || Error::NotFound("Version field not found in project() of meson.build".to_string());
+
+  let start = regex::Regex::new(r"(?m)^\s*project\s*\(")?
+    .find(content)
+    .ok_or_else(not_found)?
+    .end();
+  let arguments = &content[start..start + call_length(&content[start..])];
+
+  let captures = regex::Regex::new(r"\bversion\s*:\s*'([^']*)'")?
+    .captures(arguments)
+    .ok_or_else(not_found)?;
+  let version = captures.get(1).ok_or_else(not_found)?;
+  Ok(start + version.start()..start + version.end())
+}
+
+/// Length of the arguments of a call up to its closing parenthesis, skipping
+/// strings and comments.
+fn call_length(arguments: &str) -> usize {
+  let mut depth = 0;
+  let mut chars = arguments.char_indices();
+  while let Some((index, char)) = chars.next() {
+    match char {
+      '\'' => {
+        let mut escaped = false;
+        for (_, char) in chars.by_ref() {
+          match char {
+            '\\' if !escaped => escaped = true,
+            '\'' if !escaped => break,
+            _ => escaped = false,
+          }
+        }
+      }
+      '#' => {
+        for (_, char) in chars.by_ref() {
+          if char == '\n' {
+            break;
+          }
+        }
+      }
+      '(' | '[' | '{' => depth += 1,
+      ')' | ']' | '}' if depth > 0 => depth -= 1,
+      ')' => return index,
+      _ => {}
+    }
+  }
+  arguments.len()
+}
@@ -1,18 +1,30 @@
-use anyhow::{Context, Result};
+use crate::error::{Error, Result};
 use std::path::{Path, PathBuf};
 
+/// Plugin metafile with the version at a top-level `version` key, or at a
+/// nested key like `plugin.version`.
 pub struct YamlMetafile {
   path: PathBuf,
+  /// Keys leading to the version, outermost first.
+  key: Vec<String>,
 }
 
-impl super::VersionIO for YamlMetafile {
-  fn new(path: &Path) -> Self {
+impl YamlMetafile {
+  /// Handles the metafile at `path` with the version at the dotted `key`.
+  pub fn with_key(path: &Path, key: &str) -> Self {
     Self {
       path: path.to_path_buf(),
+      key: key.split('.').map(str::to_string).collect(),
     }
   }
+}
 
-  fn new_auto() -> Result<Self> {
+impl super::VersionIO for YamlMetafile {
+  fn new(path: &Path) -> Self {
+    Self::with_key(path, "version")
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
     let path_candidates = vec![
       "meta.yml",
       "meta/meta.yml",
@@ -21,34 +33,76 @@ impl super::VersionIO for YamlMetafile {
     ];
     let mut path = None;
     for path_candidate in path_candidates {
-      let path_candidate = PathBuf::from(path_candidate);
+      let path_candidate = dir.join(path_candidate);
       if path_candidate.exists() {
         path = Some(path_candidate);
         break;
       }
     }
     Ok(Self::new(
-      path.context("Failed to find meta file")?.as_path(),
+      path
+        .ok_or_else(|| Error::NotFound("plugin metafile not found".to_string()))?
+        .as_path(),
     ))
   }
 
+  fn kind(&self) -> &'static str {
+    "metafile"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
   fn read(&self) -> Result<semver::Version> {
-    let content = std::fs::read_to_string(&self.path)?;
-    let yaml: serde_norway::Value =
-      serde_norway::from_str(&content).context("Failed to parse yaml")?;
-    let version = yaml["version"]
-      .as_str()
-      .context("Failed to parse version")?;
-    Ok(semver::Version::parse(version).context("Failed to parse version")?)
-  }
-
-  fn write(&self, version: &semver::Version) -> Result<()> {
-    let content = std::fs::read_to_string(&self.path)?;
-    let mut yaml: serde_norway::Value =
-      serde_norway::from_str(&content).context("Failed to parse yaml")?;
-    yaml["version"] = serde_norway::Value::String(version.to_string());
-    let yaml = serde_norway::to_string(&yaml)?;
-    std::fs::write(&self.path, yaml)?;
-    Ok(())
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let yaml = self.parse(&content)?;
+    let version = self
+      .key
+      .iter()
+      .try_fold(&yaml, |value, key| value.get(key))
+      .and_then(serde_norway::Value::as_str)
+      .ok_or_else(|| self.not_found())?;
+    super::parse_version(version)
+  }
+
+  /// Replaces the version scalar only. Versions in flow mappings or other
+  /// layouts the in-place edit does not handle get the document re-serialized.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let keys: Vec<&str> = self.key.iter().map(String::as_str).collect();
+    if let Some(span) = super::yaml_edit::value_span(content, &keys) {
+      let mut patched = content.to_string();
+      patched.replace_range(span, &version.to_string());
+      return Ok(patched);
+    }
+
+    let mut yaml = self.parse(content)?;
+    let value = self
+      .key
+      .iter()
+      .try_fold(&mut yaml, |value, key| value.get_mut(key))
+      .filter(|value| value.is_string())
+      .ok_or_else(|| self.not_found())?;
+    *value = serde_norway::Value::String(version.to_string());
+    serde_norway::to_string(&yaml).map_err(|source| Error::Yaml {
+      path: self.path.clone(),
+      source,
+    })
+  }
+}
+
+impl YamlMetafile {
+  fn not_found(&self) -> Error {
+    Error::NotFound(format!(
+      "Version field '{}' not found in plugin metafile",
+      self.key.join(".")
+    ))
+  }
+
+  fn parse(&self, content: &str) -> Result<serde_norway::Value> {
+    serde_norway::from_str(content).map_err(|source| Error::Yaml {
+      path: self.path.clone(),
+      source,
+    })
   }
 }
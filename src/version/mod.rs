@@ -1,13 +1,52 @@
+//! Reading and writing project versions in build system and metadata files.
+
+mod bump;
 mod cargo;
+pub mod changelog;
 mod cmake;
+mod component;
+mod config;
 mod conan;
+mod conventional;
+mod diff;
+mod emit_header;
 mod export_header;
+mod glob;
 mod interfaces;
+mod json_edit;
 mod lua_metafile;
+mod meson;
 mod metafile;
+mod package_json;
+mod pyproject;
+mod python;
+mod regex_source;
+mod report;
 mod run;
+mod setup_cfg;
+mod toml_doc;
+mod vcpkg;
+mod yaml_edit;
 
+pub(crate) use self::{interfaces::parse_version, run::run};
 pub use self::{
-  cargo::CargoFile, cmake::CmakeFile, conan::ConanFile, export_header::ExportHeaderFile,
-  interfaces::VersionIO, metafile::YamlMetafile, lua_metafile::LuaMetafile, run::run,
+  bump::{Bump, bump, next_prerelease, with_build, with_prerelease},
+  cargo::{CargoFile, CargoLockFile},
+  cmake::CmakeFile,
+  component::Component,
+  conan::{ConanDataFile, ConanFile},
+  conventional::{ConventionalCommit, bump_level},
+  emit_header::{GitInfo, HeaderOptions},
+  export_header::{ExportHeaderFile, HeaderSearch},
+  config::{Config, Source},
+  interfaces::{KINDS, Kind, VersionIO, kind_name},
+  lua_metafile::LuaMetafile,
+  meson::MesonFile,
+  metafile::YamlMetafile,
+  package_json::{PackageJsonFile, PackageLockFile},
+  pyproject::PyprojectFile,
+  regex_source::RegexFile,
+  report::{OutputFileReport, ComponentReport, FileReport, FileStatus, OutputFormat, VersionReport},
+  setup_cfg::SetupCfgFile,
+  vcpkg::VcpkgFile,
 };
@@ -0,0 +1,135 @@
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+pub struct PackageJsonFile {
+  path: PathBuf,
+}
+
+/// npm lockfile, which repeats the version of the root package.
+pub struct PackageLockFile {
+  path: PathBuf,
+}
+
+impl super::VersionIO for PackageJsonFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    find(dir, "package.json").map(|path| Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "npm"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    read_version(&self.path)
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    patch_strings(&self.path, content, &[&["version"]], version)
+  }
+}
+
+impl super::VersionIO for PackageLockFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    find(dir, "package-lock.json").map(|path| Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "npm-lock"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    read_version(&self.path)
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    // Lockfiles from npm 7 on also list the root package under `packages[""]`.
+    patch_strings(
+      &self.path,
+      content,
+      &[&["version"], &["packages", "", "version"]],
+      version,
+    )
+  }
+}
+
+fn find(dir: &Path, name: &str) -> Result<PathBuf> {
+  let path = dir.join(name);
+  if path.exists() {
+    Ok(path)
+  } else {
+    Err(Error::NotFound(format!("{} not found", name)))
+  }
+}
+
+/// Version of the package described by the JSON file at `path`.
+fn read_version(path: &Path) -> Result<semver::Version> {
+  let content = std::fs::read_to_string(path).map_err(Error::io(path))?;
+  let json: serde_json::Value = serde_json::from_str(&content).map_err(|source| Error::Json {
+    path: path.to_path_buf(),
+    source,
+  })?;
+  let version = json["version"].as_str().ok_or_else(|| {
+    Error::NotFound(format!(
+      "Version field not found in {}",
+      crate::compilers::common::display_path(path, None)
+    ))
+  })?;
+  super::parse_version(version)
+}
+
+/// Replaces the string values at `keys` in place, leaving the rest of the
+/// document byte for byte as it was. The first key path must exist.
+fn patch_strings(
+  path: &Path,
+  content: &str,
+  keys: &[&[&str]],
+  version: &semver::Version,
+) -> Result<String> {
+  serde_json::from_str::<serde_json::Value>(content).map_err(|source| Error::Json {
+    path: path.to_path_buf(),
+    source,
+  })?;
+
+  let spans: Vec<Option<Range<usize>>> = keys
+    .iter()
+    .map(|key| super::json_edit::string_span(content, key))
+    .collect();
+  if spans[0].is_none() {
+    return Err(Error::NotFound(format!(
+      "Version field not found in {}",
+      crate::compilers::common::display_path(path, None)
+    )));
+  }
+
+  // Replace back to front so the earlier spans stay valid.
+  let mut spans: Vec<Range<usize>> = spans.into_iter().flatten().collect();
+  spans.sort_by_key(|span| std::cmp::Reverse(span.start));
+  let mut patched = content.to_string();
+  for span in spans {
+    patched.replace_range(span, &version.to_string());
+  }
+  Ok(patched)
+}
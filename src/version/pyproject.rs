@@ -0,0 +1,76 @@
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+use toml_edit::Item;
+
+/// Tables that may hold the version: PEP 621 metadata, then Poetry's.
+const TABLES: [&[&str]; 2] = [&["project"], &["tool", "poetry"]];
+
+pub struct PyprojectFile {
+  path: PathBuf,
+}
+
+impl PyprojectFile {
+  fn not_found(&self) -> Error {
+    Error::NotFound(
+      "Version field not found in [project] or [tool.poetry] section of pyproject.toml".to_string(),
+    )
+  }
+}
+
+impl super::VersionIO for PyprojectFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("pyproject.toml");
+    if !path.exists() {
+      return Err(Error::NotFound("pyproject.toml not found".to_string()));
+    }
+    Ok(Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "pyproject"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let document = super::toml_doc::parse(&self.path, &content)?;
+    let version = TABLES
+      .iter()
+      .find_map(|table| version_item(document.as_item(), table)?.as_str())
+      .ok_or_else(|| self.not_found())?;
+    super::parse_version(version)
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let mut document = super::toml_doc::parse(&self.path, content)?;
+    let mut replaced = false;
+    for table in TABLES {
+      if let Some(item) = super::toml_doc::get_mut(document.as_item_mut(), table)
+        .and_then(|table| super::toml_doc::get_mut(table, &["version"]))
+      {
+        replaced |= super::toml_doc::set_string(item, &version.to_string());
+      }
+    }
+
+    if !replaced {
+      return Err(self.not_found());
+    }
+    Ok(document.to_string())
+  }
+}
+
+fn version_item<'a>(root: &'a Item, table: &[&str]) -> Option<&'a Item> {
+  table
+    .iter()
+    .try_fold(root, |item, key| item.get(key))?
+    .get("version")
+}
@@ -0,0 +1,189 @@
+//! Tokens of Python sources, grouped in logical lines: enough to find class
+//! definitions and assignments without running the code.
+
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Kind {
+  Name,
+  Number,
+  String,
+  /// Operators and delimiters, like `=`, `==` or `(`.
+  Op,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Token<'a> {
+  pub kind: Kind,
+  pub text: &'a str,
+  pub span: Range<usize>,
+}
+
+/// A statement, which may span several physical lines.
+pub(super) struct Line<'a> {
+  /// Column of the first token.
+  pub indent: usize,
+  pub tokens: Vec<Token<'a>>,
+}
+
+impl Token<'_> {
+  pub fn is(&self, kind: Kind, text: &str) -> bool {
+    self.kind == kind && self.text == text
+  }
+
+  /// Span of the contents of a plain or raw string literal, without its
+  /// quotes; none for f-strings and bytes, whose value is not spelled out.
+  pub fn literal(&self) -> Option<Range<usize>> {
+    if self.kind != Kind::String {
+      return None;
+    }
+    let prefix = self.text.len() - self.text.trim_start_matches(char::is_alphabetic).len();
+    if self.text[..prefix]
+      .chars()
+      .any(|char| !matches!(char, 'r' | 'R' | 'u' | 'U'))
+    {
+      return None;
+    }
+    let body = &self.text[prefix..];
+    let quotes = match body.starts_with("\"\"\"") || body.starts_with("'''") {
+      true => 3,
+      false => 1,
+    };
+    let start = self.span.start + prefix + quotes;
+    let end = self.span.end.checked_sub(quotes)?;
+    (start <= end).then_some(start..end)
+  }
+}
+
+/// The logical lines of `source`, without comments.
+pub(super) fn lines(source: &str) -> Vec<Line<'_>> {
+  let mut lines = Vec::new();
+  let mut tokens = Vec::new();
+  let mut indent = 0;
+  let mut line_start = 0;
+  let mut depth = 0usize;
+  let mut chars = source.char_indices().peekable();
+
+  while let Some((start, char)) = chars.next() {
+    let kind = match char {
+      '\n' => {
+        line_start = start + 1;
+        if depth == 0 && !tokens.is_empty() {
+          lines.push(Line {
+            indent,
+            tokens: std::mem::take(&mut tokens),
+          });
+        }
+        continue;
+      }
+      '\\' => {
+        // Line continuation.
+        chars.next_if(|(_, char)| *char == '\r');
+        chars.next_if(|(_, char)| *char == '\n');
+        continue;
+      }
+      '#' => {
+        while chars.next_if(|(_, char)| *char != '\n').is_some() {}
+        continue;
+      }
+      _ if char.is_whitespace() => continue,
+      '"' | '\'' => {
+        string(source, start, &mut chars);
+        Kind::String
+      }
+      _ if char.is_alphabetic() || char == '_' => {
+        while chars
+          .next_if(|(_, char)| char.is_alphanumeric() || *char == '_')
+          .is_some()
+        {}
+        let name_end = chars_pos(&mut chars, source);
+        match chars.peek() {
+          Some((_, '"' | '\'')) if is_string_prefix(&source[start..name_end]) => {
+            chars.next();
+            string(source, name_end, &mut chars);
+            Kind::String
+          }
+          _ => Kind::Name,
+        }
+      }
+      _ if char.is_ascii_digit() => {
+        while chars
+          .next_if(|(_, char)| char.is_alphanumeric() || matches!(char, '.' | '_'))
+          .is_some()
+        {}
+        Kind::Number
+      }
+      _ => {
+        match char {
+          '(' | '[' | '{' => depth += 1,
+          ')' | ']' | '}' => depth = depth.saturating_sub(1),
+          _ => {}
+        }
+        let two_chars = chars.peek().is_some_and(|(_, next)| {
+          (*next == '=' && "=!<>+-*/%&|^:@".contains(char)) || (char == '-' && *next == '>')
+        });
+        if two_chars {
+          chars.next();
+        }
+        Kind::Op
+      }
+    };
+
+    let end = chars_pos(&mut chars, source);
+    if tokens.is_empty() {
+      indent = source[line_start..start].chars().count();
+    }
+    tokens.push(Token {
+      kind,
+      text: &source[start..end],
+      span: start..end,
+    });
+  }
+  if !tokens.is_empty() {
+    lines.push(Line { indent, tokens });
+  }
+  lines
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+/// Offset of the next character.
+fn chars_pos(chars: &mut Chars<'_>, source: &str) -> usize {
+  chars.peek().map_or(source.len(), |(index, _)| *index)
+}
+
+fn is_string_prefix(name: &str) -> bool {
+  name.len() <= 2
+    && name
+      .chars()
+      .all(|char| matches!(char.to_ascii_lowercase(), 'r' | 'b' | 'u' | 'f'))
+}
+
+/// Moves past the string whose opening quote is at `start`.
+fn string(source: &str, start: usize, chars: &mut Chars<'_>) {
+  let quote = &source[start..start + 1];
+  let triple = source[start..].starts_with(&quote.repeat(3));
+  if triple {
+    chars.next();
+    chars.next();
+  }
+  while let Some((index, char)) = chars.next() {
+    match char {
+      '\\' => {
+        chars.next();
+      }
+      '\n' if !triple => return,
+      _ if source[index..].starts_with(quote) => {
+        if !triple {
+          return;
+        }
+        if source[index..].starts_with(&quote.repeat(3)) {
+          chars.next();
+          chars.next();
+          return;
+        }
+      }
+      _ => {}
+    }
+  }
+}
@@ -0,0 +1,115 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+/// Version in the first `version = 1.2.3`, `"version": "1.2.3"` or
+/// `VERSION 1.2.3`-like assignment, for sources declared without a pattern.
+const DEFAULT_PATTERN: &str =
+  r#"(?i)\bversion\b["']?\s*[:=]?\s*["']?(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)"#;
+
+/// Any text file holding the version where a regular expression says, like a
+/// README badge or `PROJECT_NUMBER` of a Doxyfile. The version is the capture
+/// group named `version`, or the first one.
+///
+/// Every match of a declared pattern is updated, so they must all agree; the
+/// built-in pattern only reads and updates its first match.
+pub struct RegexFile {
+  path: PathBuf,
+  pattern: Regex,
+  every_match: bool,
+}
+
+impl RegexFile {
+  /// Handles the file at `path` with `pattern`, which must have a capture group.
+  pub fn with_pattern(path: &Path, pattern: &str) -> Result<Self> {
+    let pattern = Regex::new(pattern)?;
+    if pattern.captures_len() < 2 {
+      return Err(Error::Config(format!(
+        "pattern '{}' has no capture group for the version",
+        pattern
+      )));
+    }
+    Ok(Self {
+      path: path.to_path_buf(),
+      pattern,
+      every_match: true,
+    })
+  }
+
+  /// Spans of the version the file holds in `content`: the first match of the
+  /// built-in pattern, or every match of a declared one.
+  fn spans(&self, content: &str) -> Result<Vec<Range<usize>>> {
+    let mut spans: Vec<Range<usize>> = self
+      .pattern
+      .captures_iter(content)
+      .filter_map(|captures| captures.name("version").or_else(|| captures.get(1)))
+      .map(|version| version.range())
+      .collect();
+    if spans.is_empty() {
+      return Err(Error::NotFound(format!(
+        "Pattern '{}' does not match {}",
+        self.pattern,
+        crate::compilers::common::display_path(&self.path, None)
+      )));
+    }
+    if !self.every_match {
+      spans.truncate(1);
+    }
+    let first = &content[spans[0].clone()];
+    if let Some(other) = spans
+      .iter()
+      .map(|span| &content[span.clone()])
+      .find(|version| version != &first)
+    {
+      return Err(Error::Config(format!(
+        "pattern '{}' matches both {} and {} in {}",
+        self.pattern,
+        first,
+        other,
+        crate::compilers::common::display_path(&self.path, None)
+      )));
+    }
+    Ok(spans)
+  }
+}
+
+impl super::VersionIO for RegexFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      every_match: false,
+      ..Self::with_pattern(path, DEFAULT_PATTERN).expect("the default pattern is valid")
+    }
+  }
+
+  fn new_auto_in(_dir: &Path) -> Result<Self> {
+    Err(Error::NotFound(
+      "Pattern sources are only declared in the configuration".to_string(),
+    ))
+  }
+
+  fn kind(&self) -> &'static str {
+    "regex"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let span = self.spans(&content)?.remove(0);
+    super::parse_version(&content[span])
+  }
+
+  /// Replaces the version in every span [`RegexFile::read`] agrees with.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let mut patched = content.to_string();
+    for span in self.spans(content)?.into_iter().rev() {
+      patched.replace_range(span, &version.to_string());
+    }
+    Ok(patched)
+  }
+}
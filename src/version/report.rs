@@ -0,0 +1,151 @@
+use super::VersionIO;
+use crate::error::Error;
+use serde::Serialize;
+
+/// Output format of `wucc version`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+  /// Human readable table.
+  #[default]
+  Plain,
+  Json,
+  Yaml,
+}
+
+/// Outcome for a single version file.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileStatus {
+  /// The version was read (`--show`).
+  Read,
+  /// The file now holds the new version.
+  Updated,
+  /// The file already held the new version.
+  Unchanged,
+  /// Reading or writing the file failed; see `error`.
+  Failed,
+  /// The file matches the reference version (`--check`).
+  InSync,
+  /// The file differs from the reference version (`--check`).
+  OutOfSync,
+  /// The file would hold the new version (`--dry-run`).
+  WouldUpdate,
+  /// The change was declined (`--interactive`).
+  Skipped,
+}
+
+/// Structured result of `wucc version` for one file.
+#[derive(Serialize, Debug)]
+pub struct FileReport {
+  pub kind: &'static str,
+  pub name: String,
+  pub path: String,
+  pub old_version: Option<String>,
+  pub new_version: Option<String>,
+  pub status: FileStatus,
+  pub error: Option<String>,
+  /// Unified diff of the change, with `--dry-run` and `--interactive`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub diff: Option<String>,
+}
+
+/// Outcome for a file generated along with the version files (`--changelog`,
+/// `--emit-header`).
+#[derive(Serialize, Debug)]
+pub struct OutputFileReport {
+  pub path: String,
+  pub status: FileStatus,
+  /// Unified diff of the change, with `--dry-run` and `--interactive`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub diff: Option<String>,
+}
+
+/// A component of a monorepo and its version files (`--components`).
+#[derive(Serialize, Debug)]
+pub struct ComponentReport {
+  pub name: String,
+  pub path: String,
+  /// The lowest version among the files of the component.
+  pub version: Option<String>,
+  pub files: Vec<FileReport>,
+}
+
+/// Structured result of `wucc version`.
+#[derive(Serialize, Debug, Default)]
+pub struct VersionReport {
+  /// The resolved version: the lowest one present for `--show`, otherwise the new one.
+  pub version: Option<String>,
+  /// The component the new version applies to (`--component`).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub component: Option<String>,
+  /// What `--check` compared against: the kind of the reference file, or `max`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reference: Option<String>,
+  pub files: Vec<FileReport>,
+  /// Every component of the monorepo (`--components`).
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub components: Vec<ComponentReport>,
+  /// The changelog section added for the new version (`--changelog`).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub changelog: Option<OutputFileReport>,
+  /// The generated version header (`--emit-header`).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub header: Option<OutputFileReport>,
+  /// Hash of the version commit (`--commit`).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub commit: Option<String>,
+  /// Name of the version tag (`--tag`).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tag: Option<String>,
+}
+
+impl FileReport {
+  pub(crate) fn new(name: &str, file: &dyn VersionIO) -> Self {
+    Self {
+      kind: file.kind(),
+      name: name.to_string(),
+      path: crate::compilers::common::display_path(file.path(), None),
+      old_version: None,
+      new_version: None,
+      status: FileStatus::Read,
+      error: None,
+      diff: None,
+    }
+  }
+
+  pub(crate) fn failed(mut self, error: &Error) -> Self {
+    self.status = FileStatus::Failed;
+    self.error = Some(error_message(error));
+    self
+  }
+}
+
+impl VersionReport {
+  /// Prints the report to stdout in `format`; plain output is printed as a table instead.
+  pub(crate) fn print(&self, format: OutputFormat) {
+    // Reports only hold strings and options, which always serialize.
+    match format {
+      OutputFormat::Json => println!(
+        "{}",
+        serde_json::to_string_pretty(self).expect("version report serializes to JSON")
+      ),
+      OutputFormat::Yaml => print!(
+        "{}",
+        serde_norway::to_string(self).expect("version report serializes to YAML")
+      ),
+      OutputFormat::Plain => {}
+    }
+  }
+}
+
+/// `error` followed by its causes, on a single line.
+fn error_message(error: &Error) -> String {
+  let mut message = error.to_string();
+  let mut source = std::error::Error::source(error);
+  while let Some(cause) = source {
+    message.push_str(": ");
+    message.push_str(&cause.to_string());
+    source = cause.source();
+  }
+  message
+}
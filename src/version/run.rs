@@ -1,6 +1,40 @@
-use super::VersionIO;
-use anyhow::{Context, Result};
+use super::{
+  Component, Config, VersionIO,
+  bump::{Bump, bump, next_prerelease, with_build, with_prerelease},
+  changelog::{Release, write_release},
+  conventional::bump_level,
+  emit_header::{GitInfo, HeaderOptions, write_header},
+  report::{
+    ComponentReport, FileReport, FileStatus, OutputFileReport, OutputFormat, VersionReport,
+  },
+};
+use crate::error::{Error, Result};
+use crate::git::Repository;
 use colored::Colorize;
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+/// What a new version applies to: the whole project, or a single component of
+/// a monorepo with `--component`.
+struct Scope {
+  component: Option<String>,
+  /// Prefix of the version tags.
+  tag_prefix: String,
+  /// Directory the commit history is limited to.
+  path: Option<PathBuf>,
+}
+
+impl Default for Scope {
+  fn default() -> Self {
+    Self {
+      component: None,
+      tag_prefix: "v".to_string(),
+      path: None,
+    }
+  }
+}
 
 fn print_version(cell_name: &str, version: &Option<semver::Version>) {
   if let Some(version) = version {
@@ -14,6 +48,32 @@ fn print_version(cell_name: &str, version: &Option<semver::Version>) {
   }
 }
 
+fn print_component(component: &Component, version: &Option<semver::Version>) {
+  let path = crate::compilers::common::display_path(&component.dir, None);
+  let location = match path == component.name {
+    true => String::new(),
+    false => format!(" ({})", path),
+  };
+  let version = match version {
+    Some(version) => version.to_string().bold().magenta(),
+    None => "unknown".red(),
+  };
+  println!("{}{} {}", component.name.bold(), location, version);
+}
+
+fn print_component_file(cell_name: &str, version: &Option<semver::Version>, last: bool) {
+  let branch = if last { "└─" } else { "├─" };
+  match version {
+    Some(version) => println!(
+      "{} {:.<25} {}",
+      branch,
+      cell_name,
+      version.to_string().bold().magenta()
+    ),
+    None => println!("{} {:.<25} {}", branch, cell_name, "unknown".red()),
+  }
+}
+
 fn print_version_diff(
   cell_name: &str,
   old_version: &Option<semver::Version>,
@@ -47,43 +107,700 @@ fn print_version_diff(
   }
 }
 
-pub fn min_version_present() -> Result<semver::Version> {
-  let files = <dyn VersionIO>::all();
-  let versions: Vec<semver::Version> = files
-    .into_iter()
-    .filter_map(|(_, file)| file.read().ok())
-    .collect();
-  versions.into_iter().min().context("No version files found")
+fn print_write_failure(cell_name: &str, old_version: &Option<semver::Version>) {
+  let old_version = match old_version {
+    Some(version) => version.to_string().bold().magenta(),
+    None => "unknown".red(),
+  };
+  println!(
+    "• {:.<25} {} -> {}",
+    cell_name,
+    old_version,
+    "not written".red().bold()
+  );
 }
 
-pub fn run(args: crate::args::VersionArgs) -> Result<()> {
-  let files = <dyn VersionIO>::all();
-
-  if args.show {
-    for (name, file) in &files {
-      print_version(name, &file.read().ok());
+fn print_check(
+  cell_name: &str,
+  version: &Option<(semver::Version, bool)>,
+  expected: &semver::Version,
+) {
+  match version {
+    Some((version, true)) => {
+      println!(
+        "• {:.<25} {}",
+        cell_name,
+        version.to_string().bold().green()
+      )
     }
-    return Ok(());
+    Some((version, false)) => println!(
+      "• {:.<25} {} (expected {})",
+      cell_name,
+      version.to_string().bold().red(),
+      expected.to_string().bold()
+    ),
+    None => println!(
+      "• {:.<25} {} (expected {})",
+      cell_name,
+      "unknown".red(),
+      expected.to_string().bold()
+    ),
   }
+}
+
+fn print_git_action(action: &str, detail: &str) {
+  println!("{} {}", action.green(), detail.bold());
+}
+
+pub(crate) fn print_output(path: &Path, detail: &str, status: FileStatus) {
+  let action = match status {
+    FileStatus::WouldUpdate => "Would update",
+    FileStatus::Unchanged => "Unchanged",
+    FileStatus::Skipped => "Skipped",
+    _ => "Updated",
+  };
+  let path = crate::compilers::common::display_path(path, None);
+  print_git_action(action, &format!("{} ({})", path, detail));
+}
 
-  let mut ver = match args.assign {
-    Some(version) => semver::Version::parse(&version)?,
-    None => min_version_present().unwrap_or_else(|_| semver::Version::new(0, 1, 0)),
+fn print_skipped(cell_name: &str, old_version: &Option<semver::Version>) {
+  let old_version = match old_version {
+    Some(version) => version.to_string().bold().magenta(),
+    None => "unknown".red(),
   };
-  
+  println!("• {:.<25} {} (skipped)", cell_name, old_version);
+}
+
+pub(crate) fn print_diff(diff: &str) {
+  for line in diff.lines() {
+    if line.starts_with("+++") || line.starts_with("---") {
+      println!("{}", line.bold());
+    } else if line.starts_with('+') {
+      println!("{}", line.green());
+    } else if line.starts_with('-') {
+      println!("{}", line.red());
+    } else if line.starts_with("@@") {
+      println!("{}", line.cyan());
+    } else {
+      println!("{}", line);
+    }
+  }
+}
+
+fn confirm(path: &str) -> Result<bool> {
+  print!("Apply changes to {}? [y/N] ", path.bold());
+  let stdin = std::path::Path::new("<stdin>");
+  std::io::stdout().flush().map_err(Error::io(stdin))?;
+
+  let mut answer = String::new();
+  std::io::stdin()
+    .read_line(&mut answer)
+    .map_err(Error::io(stdin))?;
+  Ok(matches!(
+    answer.trim().to_ascii_lowercase().as_str(),
+    "y" | "yes"
+  ))
+}
+
+/// Applies `version` to `file` unless `--dry-run` is set or the user declines
+/// it with `--interactive`, returning the outcome and the diff of the change.
+fn update(
+  file: &dyn VersionIO,
+  version: &semver::Version,
+  args: &crate::args::VersionArgs,
+  plain: bool,
+) -> Result<(FileStatus, String)> {
+  let (content, patched) = file.preview(version)?;
+  apply(
+    file.path(),
+    &content,
+    &patched,
+    args.dry_run,
+    args.interactive,
+    plain,
+  )
+}
+
+/// Replaces the `content` of `path` with `patched` unless `dry_run` is set or
+/// the user declines it when `interactive`, showing the diff first if `plain`.
+pub(crate) fn apply(
+  path: &Path,
+  content: &str,
+  patched: &str,
+  dry_run: bool,
+  interactive: bool,
+  plain: bool,
+) -> Result<(FileStatus, String)> {
+  let display = crate::compilers::common::display_path(path, None);
+  let diff = super::diff::unified(&display, content, patched);
+
+  if content == patched {
+    return Ok((FileStatus::Unchanged, diff));
+  }
+  if dry_run {
+    return Ok((FileStatus::WouldUpdate, diff));
+  }
+  if interactive {
+    if plain {
+      print_diff(&diff);
+    }
+    if !confirm(&display)? {
+      return Ok((FileStatus::Skipped, diff));
+    }
+  }
+
+  std::fs::write(path, patched).map_err(Error::io(path))?;
+  Ok((FileStatus::Updated, diff))
+}
+
+/// Lowest version among the version files selected by `wucc.yml`.
+pub fn min_version_present() -> Result<semver::Version> {
+  min_version(&Config::load(None)?.files()?)
+}
+
+/// Lowest version among `files`.
+fn min_version(files: &[(String, Box<dyn VersionIO>)]) -> Result<semver::Version> {
+  files
+    .iter()
+    .filter_map(|(_, file)| file.read().ok())
+    .min()
+    .ok_or_else(|| Error::NotFound("No version files found".to_string()))
+}
+
+/// Bump requested with `--bump-major`, `--bump-minor` or `--bump-patch`.
+fn requested_bump(args: &crate::args::VersionArgs) -> Option<Bump> {
   if args.bump_major {
-    ver = semver::Version::new(ver.major + 1, 0, 0);
+    Some(Bump::Major)
   } else if args.bump_minor {
-    ver = semver::Version::new(ver.major, ver.minor + 1, 0);
+    Some(Bump::Minor)
   } else if args.bump_patch {
-    ver = semver::Version::new(ver.major, ver.minor, ver.patch + 1);
+    Some(Bump::Patch)
+  } else {
+    None
+  }
+}
+
+/// Bump called for by the Conventional Commits since the last version tag,
+/// along with a description of where it came from.
+fn detect_bump(scope: &Scope) -> Result<(Option<Bump>, String)> {
+  let repository = Repository::discover(Path::new("."))?;
+  let tag = repository
+    .latest_version_tag(&scope.tag_prefix)?
+    .map(|(tag, _)| tag);
+  let messages = repository.commit_messages(tag.as_deref(), scope.path.as_deref())?;
+
+  let origin = format!(
+    "{} commit(s) since {}",
+    messages.len(),
+    tag.as_deref().unwrap_or("the first commit")
+  );
+  Ok((bump_level(messages.iter().map(String::as_str)), origin))
+}
+
+/// Version to write to `files`, as requested by `args`, bumping `part` first.
+fn next_version(
+  args: &crate::args::VersionArgs,
+  part: Option<Bump>,
+  files: &[(String, Box<dyn VersionIO>)],
+) -> Result<semver::Version> {
+  let mut ver = match &args.assign {
+    Some(version) => super::parse_version(version)?,
+    None => min_version(files).unwrap_or_else(|_| semver::Version::new(0, 1, 0)),
+  };
+
+  if let Some(part) = part {
+    ver = bump(&ver, part);
+  }
+
+  if let Some(id) = &args.pre {
+    ver = match part {
+      Some(_) => with_prerelease(&ver, id, 1)?,
+      None => next_prerelease(&ver, id)?,
+    };
+  }
+  if args.release {
+    ver = semver::Version::new(ver.major, ver.minor, ver.patch);
+  }
+  if let Some(build) = &args.build {
+    ver = with_build(&ver, build)?;
+  }
+
+  Ok(ver)
+}
+
+/// Runs the `version` subcommand.
+pub(crate) fn run(args: crate::args::VersionArgs) -> Result<()> {
+  let config = Config::load(args.config.as_deref())?.with_args(&args);
+  if args.components {
+    let mut found = Component::discover(Path::new(""), &config.version.headers)?;
+    for component in &mut found {
+      let files = std::mem::take(&mut component.files);
+      component.files = config.merge(files, Some(&*component))?;
+    }
+    return components(&found, args.format);
+  }
+  let (files, scope) = match &args.component {
+    Some(name) => {
+      let mut component = Component::find(Path::new(""), name, &config.version.headers)?;
+      let scope = Scope {
+        component: Some(component.name.clone()),
+        tag_prefix: component.tag_prefix(),
+        path: component.history_path().map(Path::to_path_buf),
+      };
+      let files = std::mem::take(&mut component.files);
+      (config.merge(files, Some(&component))?, scope)
+    }
+    None => (config.files()?, Scope::default()),
+  };
+
+  if args.show {
+    return show(&files, args.format);
+  }
+  if args.check {
+    return check(&files, args.reference.as_deref(), args.format);
+  }
+
+  let changes_version = args.assign.is_some()
+    || args.auto
+    || args.bump_major
+    || args.bump_minor
+    || args.bump_patch
+    || args.pre.is_some()
+    || args.release
+    || args.build.is_some();
+  if args.get && !changes_version && args.emit_header.is_none() {
+    println!("{}", min_version(&files)?);
+    return Ok(());
   }
 
+  let plain = args.format == OutputFormat::Plain && !args.get;
+  let part = match args.auto {
+    true => match detect_bump(&scope)? {
+      (Some(part), origin) => {
+        if plain {
+          print_git_action("Detected", &format!("{} bump from {}", part, origin));
+        }
+        Some(part)
+      }
+      (None, origin) => return nothing_to_release(&args, &origin, &files),
+    },
+    false => requested_bump(&args),
+  };
+  let ver = next_version(&args, part, &files)?;
+  let tag_name = format!("{}{}", scope.tag_prefix, ver);
+  let repository = match args.commit || args.changelog.is_some() {
+    true => Some(open_repository(&args, &tag_name)?),
+    false => None,
+  };
+  let header_options = match &args.emit_header {
+    Some(_) => Some(header_options(&args, &ver)?),
+    None => None,
+  };
+  let release = match (&args.changelog, &repository) {
+    (Some(_), Some(repository)) => Some(Release::pending(repository, &ver.to_string())?),
+    _ => None,
+  };
+
+  let mut reports = Vec::new();
+  let mut failures = Vec::new();
+  let mut updated = Vec::new();
   for (name, file) in files {
     let old_version = file.read().ok();
-    print_version_diff(name, &old_version, &Some(ver.clone()));
-    let _ = file.write(&ver);
+    let mut report = FileReport::new(&name, file.as_ref());
+    report.old_version = old_version.as_ref().map(ToString::to_string);
+    report.new_version = Some(ver.to_string());
+    match update(file.as_ref(), &ver, &args, plain) {
+      Ok((status, diff)) => {
+        report.status = status;
+        if status == FileStatus::Updated {
+          updated.push(file.path().to_path_buf());
+        }
+        if plain {
+          match status {
+            FileStatus::Skipped => print_skipped(&name, &old_version),
+            _ => print_version_diff(&name, &old_version, &Some(ver.clone())),
+          }
+          if args.dry_run {
+            print_diff(&diff);
+          }
+        }
+        if args.dry_run || args.interactive {
+          report.diff = Some(diff);
+        }
+      }
+      Err(error) => {
+        report = report.failed(&error);
+        if plain {
+          print_write_failure(&name, &old_version);
+        }
+        failures.push((name, error));
+      }
+    }
+    reports.push(report);
+  }
+
+  let mut changelog = None;
+  if let (Some(path), Some(release)) = (&args.changelog, &release)
+    && failures.is_empty()
+  {
+    let outcome = write_release(path, release, &args, plain)?;
+    changelog = Some(generated(
+      path,
+      &release.heading,
+      outcome,
+      &args,
+      plain,
+      &mut updated,
+    ));
+  }
+  let mut header = None;
+  if let (Some(path), Some(options)) = (&args.emit_header, &header_options)
+    && failures.is_empty()
+  {
+    let outcome = write_header(path, &ver, options, &args, plain)?;
+    let detail = format!("{}_VERSION {}", options.prefix, ver);
+    header = Some(generated(
+      path,
+      &detail,
+      outcome,
+      &args,
+      plain,
+      &mut updated,
+    ));
+  }
+
+  if plain && args.dry_run {
+    println!("{}", "Dry run: no files were changed.".yellow());
+  }
+  let mut report = VersionReport {
+    version: Some(ver.to_string()),
+    component: scope.component,
+    files: reports,
+    changelog,
+    header,
+    ..Default::default()
+  };
+  if !failures.is_empty() {
+    // Nothing gets committed after a partial update.
+    if !args.get {
+      report.print(args.format);
+    }
+    return Err(Error::VersionWrite { failures });
+  }
+
+  if args.commit
+    && let Some(repository) = repository
+  {
+    let message = args
+      .message
+      .replace("{version}", &ver.to_string())
+      .replace("{tag}", &tag_name);
+    if updated.is_empty() {
+      if plain {
+        println!("{}", "Nothing to commit: no version file changed.".yellow());
+      }
+    } else {
+      let commit = repository.commit(&updated, &message)?;
+      if plain {
+        print_git_action(
+          "Committed",
+          &format!("{} file(s) as {}", updated.len(), &commit[..7]),
+        );
+      }
+      report.commit = Some(commit);
+    }
+    if args.tag {
+      repository.tag(&tag_name, &message)?;
+      if plain {
+        print_git_action("Tagged", &tag_name);
+      }
+      report.tag = Some(tag_name);
+    }
+  }
+
+  if args.get {
+    println!("{}", ver);
+  } else {
+    report.print(args.format);
+  }
+  Ok(())
+}
+
+/// Settings of the header generated with `--emit-header` for `version`. They
+/// are checked, and the state of the working tree is taken, before any file
+/// gets written.
+fn header_options(
+  args: &crate::args::VersionArgs,
+  version: &semver::Version,
+) -> Result<HeaderOptions> {
+  let git = match args.git_info {
+    true => Some(GitInfo::of(&Repository::discover(Path::new("."))?)?),
+    false => None,
+  };
+  let options = HeaderOptions {
+    prefix: args.prefix.clone().unwrap_or_default(),
+    namespace: args.header_namespace.clone(),
+    include_guard: args.include_guard.unwrap_or_default(),
+    git,
+    date: args.build_date.then(super::changelog::today),
+  };
+  super::emit_header::check(version, &options)?;
+  Ok(options)
+}
+
+/// Reports the `outcome` of writing the generated file `path`, printing it
+/// with `detail` when `plain` and noting it for `--commit` when updated.
+fn generated(
+  path: &Path,
+  detail: &str,
+  (status, diff): (FileStatus, String),
+  args: &crate::args::VersionArgs,
+  plain: bool,
+  updated: &mut Vec<PathBuf>,
+) -> OutputFileReport {
+  if status == FileStatus::Updated {
+    updated.push(path.to_path_buf());
+  }
+  if plain {
+    print_output(path, detail, status);
+    if args.dry_run {
+      print_diff(&diff);
+    }
+  }
+  OutputFileReport {
+    path: crate::compilers::common::display_path(path, None),
+    status,
+    diff: (args.dry_run || args.interactive).then_some(diff),
   }
+}
 
+/// Leaves every file as is when `--auto` finds no feature, fix or breaking change.
+fn nothing_to_release(
+  args: &crate::args::VersionArgs,
+  origin: &str,
+  files: &[(String, Box<dyn VersionIO>)],
+) -> Result<()> {
+  let current = min_version(files).ok();
+  if args.get {
+    if let Some(current) = current {
+      println!("{}", current);
+    }
+  } else if args.format == OutputFormat::Plain {
+    println!(
+      "{}",
+      format!(
+        "No feature, fix or breaking change in {}: nothing to release.",
+        origin
+      )
+      .yellow()
+    );
+  } else {
+    VersionReport {
+      version: current.map(|version| version.to_string()),
+      ..Default::default()
+    }
+    .print(args.format);
+  }
   Ok(())
 }
+
+/// Opens the repository for `--commit` and `--changelog`, checking up front
+/// that the working tree is clean when committing and that the tag is free,
+/// so nothing is written in vain.
+fn open_repository(args: &crate::args::VersionArgs, tag_name: &str) -> Result<Repository> {
+  let repository = Repository::discover(Path::new("."))?;
+  if args.commit && !args.force {
+    let changed = repository.changed_files()?;
+    if !changed.is_empty() {
+      return Err(Error::DirtyWorkingTree { files: changed });
+    }
+  }
+  if args.tag && repository.tag_exists(tag_name)? {
+    return Err(Error::Git {
+      command: format!("tag {}", tag_name),
+      message: "tag already exists".to_string(),
+    });
+  }
+  Ok(repository)
+}
+
+fn show(files: &[(String, Box<dyn VersionIO>)], format: OutputFormat) -> Result<()> {
+  if format == OutputFormat::Plain {
+    for (name, file) in files {
+      print_version(name, &file.read().ok());
+    }
+    return Ok(());
+  }
+
+  let mut versions = Vec::new();
+  let reports = files
+    .iter()
+    .map(|(name, file)| {
+      let report = FileReport::new(name, file.as_ref());
+      match file.read() {
+        Ok(version) => {
+          versions.push(version.clone());
+          FileReport {
+            old_version: Some(version.to_string()),
+            ..report
+          }
+        }
+        Err(error) => report.failed(&error),
+      }
+    })
+    .collect();
+
+  VersionReport {
+    version: versions
+      .into_iter()
+      .min()
+      .map(|version| version.to_string()),
+    files: reports,
+    ..Default::default()
+  }
+  .print(format);
+  Ok(())
+}
+
+/// Lists every component with the versions of its files (`--components`).
+fn components(components: &[Component], format: OutputFormat) -> Result<()> {
+  if components.is_empty() {
+    return Err(Error::NotFound("No components found".to_string()));
+  }
+
+  let mut reports = Vec::new();
+  for component in components {
+    let version = min_version(&component.files).ok();
+    if format == OutputFormat::Plain {
+      print_component(component, &version);
+    }
+    let mut files = Vec::new();
+    for (index, (name, file)) in component.files.iter().enumerate() {
+      let mut report = FileReport::new(name, file.as_ref());
+      let version = match file.read() {
+        Ok(version) => {
+          report.old_version = Some(version.to_string());
+          Some(version)
+        }
+        Err(error) => {
+          report = report.failed(&error);
+          None
+        }
+      };
+      if format == OutputFormat::Plain {
+        print_component_file(name, &version, index + 1 == component.files.len());
+      }
+      files.push(report);
+    }
+    reports.push(ComponentReport {
+      name: component.name.clone(),
+      path: crate::compilers::common::display_path(&component.dir, None),
+      version: version.map(|version| version.to_string()),
+      files,
+    });
+  }
+
+  VersionReport {
+    components: reports,
+    ..Default::default()
+  }
+  .print(format);
+  Ok(())
+}
+
+fn check(
+  files: &[(String, Box<dyn VersionIO>)],
+  reference: Option<&str>,
+  format: OutputFormat,
+) -> Result<()> {
+  let (reference_name, expected) = match reference {
+    Some(kind) => {
+      let (name, file) = files
+        .iter()
+        .find(|(_, file)| file.kind() == kind)
+        .ok_or_else(|| Error::NotFound(format!("Reference file '{}' not detected", kind)))?;
+      (name.to_string(), file.read()?)
+    }
+    None => (
+      "highest version".to_string(),
+      highest_version(files)
+        .ok_or_else(|| Error::NotFound("No version files found".to_string()))?,
+    ),
+  };
+
+  let mut out_of_sync = Vec::new();
+  let mut reports = Vec::new();
+  for (name, file) in files {
+    let mut report = FileReport::new(name, file.as_ref());
+    let version = match file.read() {
+      Ok(version) => {
+        let in_sync = version == expected || holds_version(file.as_ref(), &expected);
+        report.status = if in_sync {
+          FileStatus::InSync
+        } else {
+          out_of_sync.push(name.to_string());
+          FileStatus::OutOfSync
+        };
+        report.old_version = Some(version.to_string());
+        Some((version, in_sync))
+      }
+      Err(error) => {
+        out_of_sync.push(name.to_string());
+        report = report.failed(&error);
+        None
+      }
+    };
+    if format == OutputFormat::Plain {
+      print_check(name, &version, &expected);
+    }
+    reports.push(report);
+  }
+
+  VersionReport {
+    version: Some(expected.to_string()),
+    reference: Some(reference.unwrap_or("max").to_string()),
+    files: reports,
+    ..Default::default()
+  }
+  .print(format);
+
+  if out_of_sync.is_empty() {
+    Ok(())
+  } else {
+    Err(Error::VersionMismatch {
+      reference: reference_name,
+      version: expected,
+      files: out_of_sync,
+    })
+  }
+}
+
+/// Highest version found, preferring the files that can store pre-releases
+/// among those sharing the highest numeric core.
+fn highest_version(files: &[(String, Box<dyn VersionIO>)]) -> Option<semver::Version> {
+  let core = |version: &semver::Version| (version.major, version.minor, version.patch);
+  let versions: Vec<(semver::Version, bool)> = files
+    .iter()
+    .filter_map(|(_, file)| Some((file.read().ok()?, file.supports_prerelease())))
+    .collect();
+
+  let highest_core = versions.iter().map(|(version, _)| core(version)).max()?;
+  let leaders = versions
+    .iter()
+    .filter(|(version, _)| core(version) == highest_core);
+  leaders
+    .clone()
+    .filter(|(_, supports_prerelease)| *supports_prerelease)
+    .map(|(version, _)| version)
+    .max()
+    .or_else(|| leaders.map(|(version, _)| version).max())
+    .cloned()
+}
+
+/// Whether `file` already holds `version` as far as its format can tell,
+/// e.g. a CMake project version matching the numeric core of a pre-release.
+fn holds_version(file: &dyn VersionIO, version: &semver::Version) -> bool {
+  file
+    .preview(version)
+    .is_ok_and(|(content, patched)| content == patched)
+}
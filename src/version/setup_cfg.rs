@@ -0,0 +1,66 @@
+use crate::error::{Error, Result};
+use std::{
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+/// setuptools configuration, holding the version in its `[metadata]` section.
+pub struct SetupCfgFile {
+  path: PathBuf,
+}
+
+impl super::VersionIO for SetupCfgFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("setup.cfg");
+    if !path.exists() {
+      return Err(Error::NotFound("setup.cfg not found".to_string()));
+    }
+    Ok(Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "setup-cfg"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    super::parse_version(&content[version_span(&content)?])
+  }
+
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    let mut patched = content.to_string();
+    patched.replace_range(version_span(content)?, &version.to_string());
+    Ok(patched)
+  }
+}
+
+/// Span of the first version value in the `[metadata]` section of `content`.
+fn version_span(content: &str) -> Result<Range<usize>> {
+  let re = regex::Regex::new(r"^\s*version\s*[=:]\s*(\S+)\s*$")?;
+  let mut in_metadata_section = false;
+  let mut offset = 0;
+  for line in content.split_inclusive('\n') {
+    let start = offset;
+    offset += line.len();
+    if line.trim().starts_with('[') {
+      in_metadata_section = line.trim() == "[metadata]";
+    } else if in_metadata_section && let Some(captures) = re.captures(line) {
+      let value = captures.get(1).expect("the value is captured").range();
+      return Ok(start + value.start..start + value.end);
+    }
+  }
+
+  Err(Error::NotFound(
+    "Version field not found in [metadata] section of setup.cfg".to_string(),
+  ))
+}
@@ -0,0 +1,41 @@
+//! Format-preserving edits of TOML documents.
+
+use crate::error::{Error, Result};
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Value};
+
+/// Parses `content` of the TOML file at `path`.
+pub(super) fn parse(path: &Path, content: &str) -> Result<DocumentMut> {
+  content.parse().map_err(|source| Error::Toml {
+    path: path.to_path_buf(),
+    source,
+  })
+}
+
+/// Item at the table path `keys` below `item`, without creating missing tables.
+pub(super) fn get_mut<'a>(item: &'a mut Item, keys: &[&str]) -> Option<&'a mut Item> {
+  // `Item::get_mut` would insert the missing tables.
+  keys
+    .iter()
+    .try_fold(item, |item, key| item.as_table_like_mut()?.get_mut(key))
+}
+
+/// Replaces the string `item` with `new`, keeping its quote style and the
+/// whitespace and comments around it. Returns `false` when `item` is not a string.
+pub(super) fn set_string(item: &mut Item, new: &str) -> bool {
+  let Item::Value(Value::String(old)) = item else {
+    return false;
+  };
+  // Values written here never need escaping.
+  let quote = if old.display_repr().starts_with('\'') {
+    '\''
+  } else {
+    '"'
+  };
+  let mut value = format!("{}{}{}", quote, new, quote)
+    .parse::<Value>()
+    .expect("a quoted plain string is a valid TOML string");
+  *value.decor_mut() = old.decor().clone();
+  *item = Item::Value(value);
+  true
+}
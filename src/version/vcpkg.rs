@@ -0,0 +1,95 @@
+use super::json_edit::{number_span, string_span};
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Version fields of a vcpkg manifest, in order of preference.
+const VERSION_KEYS: [&str; 2] = ["version-semver", "version"];
+
+pub struct VcpkgFile {
+  path: PathBuf,
+}
+
+impl VcpkgFile {
+  fn parse(&self, content: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(content).map_err(|source| Error::Json {
+      path: self.path.clone(),
+      source,
+    })
+  }
+
+  fn not_found(&self) -> Error {
+    Error::NotFound("Version field not found in vcpkg.json".to_string())
+  }
+}
+
+impl super::VersionIO for VcpkgFile {
+  fn new(path: &Path) -> Self {
+    Self {
+      path: path.to_path_buf(),
+    }
+  }
+
+  fn new_auto_in(dir: &Path) -> Result<Self> {
+    let path = dir.join("vcpkg.json");
+    if !path.exists() {
+      return Err(Error::NotFound("vcpkg.json not found".to_string()));
+    }
+    Ok(Self::new(&path))
+  }
+
+  fn kind(&self) -> &'static str {
+    "vcpkg"
+  }
+
+  fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Only `version-semver` takes pre-release identifiers, the relaxed
+  /// `version` being dot-separated numbers.
+  fn supports_prerelease(&self) -> bool {
+    std::fs::read_to_string(&self.path)
+      .ok()
+      .and_then(|content| self.parse(&content).ok())
+      .is_some_and(|json| json[VERSION_KEYS[0]].is_string())
+  }
+
+  fn read(&self) -> Result<semver::Version> {
+    let content = std::fs::read_to_string(&self.path).map_err(Error::io(&self.path))?;
+    let json = self.parse(&content)?;
+    let version = VERSION_KEYS
+      .iter()
+      .find_map(|key| json[key].as_str())
+      .ok_or_else(|| self.not_found())?;
+    super::parse_version(version)
+  }
+
+  /// Replaces the version and resets `port-version`, which counts revisions
+  /// of the port for one version, to 0 when the version changes.
+  fn patch(&self, content: &str, version: &semver::Version) -> Result<String> {
+    self.parse(content)?;
+    let (key, span) = VERSION_KEYS
+      .iter()
+      .find_map(|key| Some((*key, string_span(content, &[key])?)))
+      .ok_or_else(|| self.not_found())?;
+    let new_version = match key {
+      "version-semver" => version.to_string(),
+      _ => format!("{}.{}.{}", version.major, version.minor, version.patch),
+    };
+    if content[span.clone()] == new_version {
+      return Ok(content.to_string());
+    }
+
+    let mut edits = vec![(span, new_version)];
+    if let Some(port_version) = number_span(content, &["port-version"]) {
+      edits.push((port_version, "0".to_string()));
+    }
+    edits.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+
+    let mut patched = content.to_string();
+    for (span, value) in edits {
+      patched.replace_range(span, &value);
+    }
+    Ok(patched)
+  }
+}
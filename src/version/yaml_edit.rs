@@ -0,0 +1,128 @@
+//! In-place edits of YAML documents, leaving everything but the edited scalar
+//! byte for byte as it was.
+
+use std::ops::Range;
+
+/// Byte range of the scalar value at the mapping keys `keys` in the YAML
+/// `content`, looking only at block mappings; quoted scalars are given without
+/// their quotes. Flow collections, block scalars, anchors and tags are left to
+/// the caller.
+pub(super) fn value_span(content: &str, keys: &[&str]) -> Option<Range<usize>> {
+  let (mut key, mut rest) = keys.split_first()?;
+  // Indentation of the key holding the mapping searched, none at the root.
+  let mut parent: Option<usize> = None;
+  // Indentation of the keys of the mapping searched, set by its first line.
+  let mut mapping_indent: Option<usize> = None;
+  let mut offset = 0;
+
+  for line in content.split_inclusive('\n') {
+    let start = offset;
+    offset += line.len();
+    let text = line.trim_end_matches(['\n', '\r']);
+    let body = text.trim_start_matches(' ');
+    if body.is_empty() || body.starts_with('#') || is_directive(text) {
+      continue;
+    }
+
+    let column = text.len() - body.len();
+    if parent.is_some_and(|parent| column <= parent) {
+      return None;
+    }
+    let indent = *mapping_indent.get_or_insert(column);
+    if column < indent {
+      return None;
+    }
+    if column > indent {
+      continue;
+    }
+
+    let Some((name, value)) = split_key(body) else {
+      continue;
+    };
+    if name != *key {
+      continue;
+    }
+    let value_start = start + text.len() - value.len();
+    match rest.split_first() {
+      None => {
+        let span = scalar(value)?;
+        return Some(value_start + span.start..value_start + span.end);
+      }
+      Some((next, others)) => {
+        // The nested mapping starts on the next line.
+        let value = value.trim_start();
+        if !value.is_empty() && !value.starts_with('#') {
+          return None;
+        }
+        parent = Some(column);
+        mapping_indent = None;
+        key = next;
+        rest = others;
+      }
+    }
+  }
+  None
+}
+
+/// Document markers and directives, which are not part of any mapping.
+fn is_directive(line: &str) -> bool {
+  line.starts_with('%') || line == "---" || line.starts_with("--- ") || line == "..."
+}
+
+/// Key of the `key: value` line `body` and the text after its colon.
+fn split_key(body: &str) -> Option<(&str, &str)> {
+  if let Some(quote @ ('"' | '\'')) = body.chars().next() {
+    let end = body[1..].find(quote)? + 1;
+    let value = body[end + 1..].strip_prefix(':')?;
+    return Some((&body[1..end], value));
+  }
+
+  let bytes = body.as_bytes();
+  for (index, &byte) in bytes.iter().enumerate() {
+    let next = bytes.get(index + 1);
+    match byte {
+      b'#' if index > 0 && bytes[index - 1] == b' ' => return None,
+      b':' if next.is_none_or(|next| *next == b' ' || *next == b'\t') => {
+        return Some((body[..index].trim_end(), &body[index + 1..]));
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Range of the scalar at the start of `value`, up to a comment; quoted
+/// scalars without their quotes.
+fn scalar(value: &str) -> Option<Range<usize>> {
+  let start = value.len() - value.trim_start().len();
+  let bytes = value.as_bytes();
+  match *bytes.get(start)? {
+    b'"' => {
+      let mut end = start + 1;
+      loop {
+        match *bytes.get(end)? {
+          b'\\' => end += 2,
+          b'"' => return Some(start + 1..end),
+          _ => end += 1,
+        }
+      }
+    }
+    b'\'' => {
+      let mut end = start + 1;
+      loop {
+        match (*bytes.get(end)?, bytes.get(end + 1)) {
+          (b'\'', Some(b'\'')) => end += 2,
+          (b'\'', _) => return Some(start + 1..end),
+          _ => end += 1,
+        }
+      }
+    }
+    b'#' | b'|' | b'>' | b'{' | b'[' | b'&' | b'*' | b'!' => None,
+    _ => {
+      let end = value[start..]
+        .find(" #")
+        .map_or(value.len(), |comment| start + comment);
+      Some(start..start + value[start..end].trim_end().len())
+    }
+  }
+}
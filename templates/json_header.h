@@ -11,20 +11,22 @@
  *
  * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
  */
-#pragma once
+{% if let Some(guard) = include_guard %}#ifndef {{ guard }}
+#define {{ guard }}{% else %}#pragma once{% endif %}
 
-#include <string>
-#include <string_view>
-{% if with_nlohmann %}#include <nlohmann/json.hpp>{% endif %}
-
-namespace {{ namespace }} {
+{% if with_nlohmann %}
+#include <nlohmann/json.hpp>
+{% endif %}
+{{ namespace_open }}
   {% for (stem, json) in json_data %}
   inline namespace {{ stem }} {
-    [[maybe_unused]]
-    inline auto constexpr {{ stem }}_c_str = {{ json | tojson | safe }};{% if with_nlohmann %}
+    {% if standard.has_attributes() %}[[maybe_unused]]
+    {% endif %}{% if standard.has_inline_variables() %}inline {% endif %}auto constexpr {{ stem }}_c_str = {{ json | tojson | safe }};{% if with_nlohmann %}
 
-    [[maybe_unused]]
-    static inline auto {{ stem }}_json = nlohmann::json::parse({{ stem }}_c_str);{% endif %}
+    {% if standard.has_attributes() %}[[maybe_unused]]
+    {% endif %}static {% if standard.has_inline_variables() %}inline {% endif %}auto {{ stem }}_json = nlohmann::json::parse({{ stem }}_c_str);{% endif %}
   } // namespace {{ stem }}
   {% endfor %}
-} // namespace {{ namespace }}
\ No newline at end of file
+{{ namespace_close }}{% if let Some(guard) = include_guard %}
+
+#endif // {{ guard }}{% endif %}
\ No newline at end of file
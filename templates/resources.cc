@@ -3,8 +3,8 @@
  * Source file(s): {% for file in files %}
  *   - {{ file }}{% endfor %}
  *
- * Header: {{ filename }}.h
- * Source: {{ filename }}.cc
+ * Header: {{ filename }}.rc.h
+ * Source: {{ filename }}.rc.cc
  *
  * Copyright (c) 2025 whs31
  * https://github.com/whs31/wucc
@@ -13,25 +13,33 @@
  * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
  */
 
-#include <array>
-#include <cstdint>
 #include "{{ filename }}.rc.h"
 
-namespace {{ namespace }} {
+#include <array>
+#include <cstddef>
+#include <cstdint>
+{% if standard.has_span() %}#include <span>
+{% endif %}{% if standard.has_string_view() %}#include <string_view>
+{% endif %}
+{{ namespace_open }}
+  namespace {
   {% for (stem, data) in text_data %}
     auto constexpr {{ stem }}_c_str = R"WUCC_DELIMITER({{ data }})WUCC_DELIMITER";
-
-    std::string_view {{ stem }}() noexcept {
-      return {{ stem }}_c_str;
-    }
   {% endfor %}
   {% for (stem, (len, data)) in binary_data %}
     auto constexpr {{ stem }}_data = std::array<std::uint8_t const, {{ len }}>{
       {{ data | indent(6) }}
     };
-
-    std::span<std::byte const> {{ stem }}() noexcept {
-      return std::span<std::byte const>(reinterpret_cast<std::byte const*>({{ stem }}_data.data()), {{ len }});
+  {% endfor %}
+  } // namespace
+  {% for stem in text_data.keys() %}
+    {{ standard.string_type() }} {{ stem }}() noexcept {
+      return {{ stem }}_c_str;
+    }
+  {% endfor %}
+  {% for (stem, (len, _)) in binary_data %}
+    {{ standard.bytes_type() }} {{ stem }}() noexcept {
+      return { reinterpret_cast<{{ standard.byte_type() }} const*>({{ stem }}_data.data()), {{ len }} };
     }
   {% endfor %}
-} // namespace {{ namespace }}
\ No newline at end of file
+{{ namespace_close }}
\ No newline at end of file
@@ -12,22 +12,40 @@
  *
  * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
  */
-#pragma once
+{% if let Some(guard) = include_guard %}#ifndef {{ guard }}
+#define {{ guard }}{% else %}#pragma once{% endif %}
 
-#include <span>
-#include <utility>
-#include <string>
-#include <string_view>
+#include <cstddef>
+{% if standard.has_span() %}#include <span>
+{% endif %}{% if standard.has_string_view() %}#include <string_view>
+{% endif %}{% if !standard.has_span() %}
+#ifndef WUCC_SPAN_VIEW_DEFINED
+#define WUCC_SPAN_VIEW_DEFINED
+namespace wucc {
+  template <typename T>
+  struct span_view {
+    T const* ptr;
+    std::size_t count;
 
-namespace {{ namespace }} {
+    T const* data() const noexcept { return ptr; }
+    std::size_t size() const noexcept { return count; }
+    T const* begin() const noexcept { return ptr; }
+    T const* end() const noexcept { return ptr + count; }
+  };
+} // namespace wucc
+#endif // WUCC_SPAN_VIEW_DEFINED
+{% endif %}
+{{ namespace_open }}
   {% for stem in text_getters %}
-    [[nodiscard]]
+    {% if standard.has_attributes() %}[[nodiscard]]
     [[maybe_unused]]
-    std::string_view {{ stem }}() noexcept;
+    {% endif %}{{ standard.string_type() }} {{ stem }}() noexcept;
   {% endfor %}
   {% for stem in binary_getters %}
-    [[nodiscard]]
+    {% if standard.has_attributes() %}[[nodiscard]]
     [[maybe_unused]]
-    std::span<std::byte const> {{ stem }}() noexcept;
+    {% endif %}{{ standard.bytes_type() }} {{ stem }}() noexcept;
   {% endfor %}
-} // namespace {{ namespace }}
\ No newline at end of file
+{{ namespace_close }}{% if let Some(guard) = include_guard %}
+
+#endif // {{ guard }}{% endif %}
\ No newline at end of file
@@ -0,0 +1,55 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Version: {{ version }}
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+{% if let Some(guard) = include_guard %}#ifndef {{ guard }}
+#define {{ guard }}{% else %}#pragma once{% endif %}
+
+#define {{ prefix }}_VERSION_MAJOR {{ version.major }}
+#define {{ prefix }}_VERSION_MINOR {{ version.minor }}
+#define {{ prefix }}_VERSION_PATCH {{ version.patch }}
+#define {{ prefix }}_VERSION_PRERELEASE "{{ version.pre }}"
+#define {{ prefix }}_VERSION_STRING "{{ version }}"
+
+/* Versions packed as 0xMMmmpppp: 8 bits of major, 8 bits of minor and 16 bits of patch. */
+#define {{ prefix }}_VERSION_ENCODE(major, minor, patch) \
+  ((major) * 0x1000000UL + (minor) * 0x10000UL + (patch))
+#define {{ prefix }}_VERSION \
+  {{ prefix }}_VERSION_ENCODE({{ prefix }}_VERSION_MAJOR, {{ prefix }}_VERSION_MINOR, {{ prefix }}_VERSION_PATCH)
+
+#define {{ prefix }}_VERSION_AT_LEAST(major, minor, patch) \
+  ({{ prefix }}_VERSION >= {{ prefix }}_VERSION_ENCODE(major, minor, patch))
+#define {{ prefix }}_VERSION_LESS_THAN(major, minor, patch) \
+  ({{ prefix }}_VERSION < {{ prefix }}_VERSION_ENCODE(major, minor, patch))
+#define {{ prefix }}_VERSION_EQUALS(major, minor, patch) \
+  ({{ prefix }}_VERSION == {{ prefix }}_VERSION_ENCODE(major, minor, patch))
+{% if let Some(git) = git %}
+#define {{ prefix }}_VERSION_COMMIT "{{ git.commit }}"
+#define {{ prefix }}_VERSION_DIRTY {% if git.dirty %}1{% else %}0{% endif %}
+{% endif %}{% if let Some(date) = date %}
+#define {{ prefix }}_VERSION_DATE "{{ date }}"
+{% endif %}{% if let Some(namespace_open) = namespace_open %}
+#ifdef __cplusplus
+{{ namespace_open }}
+  struct version {
+    static constexpr unsigned major = {{ prefix }}_VERSION_MAJOR;
+    static constexpr unsigned minor = {{ prefix }}_VERSION_MINOR;
+    static constexpr unsigned patch = {{ prefix }}_VERSION_PATCH;
+    static constexpr unsigned long packed = {{ prefix }}_VERSION;
+    static constexpr char const* prerelease = {{ prefix }}_VERSION_PRERELEASE;
+    static constexpr char const* string = {{ prefix }}_VERSION_STRING;{% if git.is_some() %}
+    static constexpr char const* commit = {{ prefix }}_VERSION_COMMIT;
+    static constexpr bool dirty = {{ prefix }}_VERSION_DIRTY != 0;{% endif %}{% if date.is_some() %}
+    static constexpr char const* date = {{ prefix }}_VERSION_DATE;{% endif %}
+  };
+{{ namespace_close }}
+#endif
+{% endif %}{% if let Some(guard) = include_guard %}
+#endif // {{ guard }}
+{% endif %}
@@ -0,0 +1,199 @@
+mod common;
+
+use common::{args, commit, copy_fixture, git, read, repository, run_wucc, write_fixture};
+use std::{
+  ffi::OsString,
+  path::Path,
+  process::{Command, Output},
+};
+
+/// A repository tagged `v1.4.2`, followed by a few Conventional Commits.
+fn with_commits() -> tempfile::TempDir {
+  let dir = repository(&["version/cargo"]);
+  git(dir.path(), &["tag", "v1.4.2"]);
+  for (file, message) in [
+    ("a.txt", "feat(cli): add --changelog"),
+    ("b.txt", "docs: describe the changelog"),
+    ("c.txt", "fix: handle empty files"),
+    ("d.txt", "refactor!: drop the old syntax"),
+    ("e.txt", "Merge branch 'topic'"),
+  ] {
+    commit(dir.path(), file, message);
+  }
+  dir
+}
+
+/// Runs wucc with a fixed release date of 2024-01-02.
+fn wucc_on_release_day(dir: &Path, args: &[OsString]) -> Output {
+  let output = Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(dir)
+    .env("NO_COLOR", "1")
+    .env("SOURCE_DATE_EPOCH", "1704153600")
+    .args(args)
+    .output()
+    .expect("failed to run wucc");
+  assert!(
+    output.status.success(),
+    "wucc failed:\n{}",
+    String::from_utf8_lossy(&output.stderr)
+  );
+  output
+}
+
+/// `text` with commit hashes replaced by `#######`.
+fn mask_hashes(text: &str) -> String {
+  regex::Regex::new(r"\([0-9a-f]{7}\)")
+    .unwrap()
+    .replace_all(text, "(#######)")
+    .to_string()
+}
+
+const HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+const RELEASE: &str = "## [1.5.0] - 2024-01-02
+
+### Added
+
+- **cli:** add --changelog (#######)
+
+### Changed
+
+- **Breaking:** drop the old syntax (#######)
+
+### Fixed
+
+- handle empty files (#######)
+
+";
+
+#[test]
+fn version_bump_adds_a_section() {
+  let dir = with_commits();
+  let output = wucc_on_release_day(
+    dir.path(),
+    &args(["version", "-m", "--changelog", "--commit", "--tag"]),
+  );
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(
+    stdout.contains("Updated CHANGELOG.md (1.5.0)"),
+    "{}",
+    stdout
+  );
+
+  assert_eq!(
+    mask_hashes(&read(dir.path(), "CHANGELOG.md")),
+    format!("{}\n{}", HEADER, RELEASE)
+  );
+  assert_eq!(
+    git(dir.path(), &["show", "--name-only", "--format=", "HEAD"]),
+    "CHANGELOG.md\nCargo.toml\n"
+  );
+}
+
+#[test]
+fn subcommand_updates_an_existing_changelog() {
+  let dir = with_commits();
+  let existing =
+    "# History\n\n## [Unreleased]\n\n- stale\n\n## [1.4.2] - 2023-12-01\n\n- Initial release.\n";
+  write_fixture(dir.path(), "CHANGELOG.md", existing.as_bytes());
+
+  let output = wucc_on_release_day(
+    dir.path(),
+    &args(["changelog", "--unreleased", "--dry-run"]),
+  );
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("Would update CHANGELOG.md (Unreleased)"));
+  assert!(!stdout.contains("-- stale"), "{}", stdout);
+  assert_eq!(read(dir.path(), "CHANGELOG.md"), existing);
+
+  wucc_on_release_day(dir.path(), &args(["version", "-a", "1.5.0"]));
+  wucc_on_release_day(dir.path(), &args(["changelog"]));
+  assert_eq!(
+    mask_hashes(&read(dir.path(), "CHANGELOG.md")),
+    format!(
+      "# History\n\n{}## [1.4.2] - 2023-12-01\n\n- Initial release.\n",
+      RELEASE.replacen("\n\n", "\n\n- stale\n\n", 1)
+    )
+  );
+
+  // A second run replaces the section instead of adding another one.
+  let output = wucc_on_release_day(dir.path(), &args(["changelog"]));
+  assert!(
+    String::from_utf8(output.stdout)
+      .unwrap()
+      .contains("Unchanged CHANGELOG.md")
+  );
+}
+
+#[test]
+fn unreleased_entries_move_into_the_release() {
+  let dir = with_commits();
+  wucc_on_release_day(dir.path(), &args(["changelog", "--unreleased"]));
+  let unreleased = read(dir.path(), "CHANGELOG.md")
+    .replace(
+      "### Added\n\n",
+      "### Added\n\n- document the new flag\n  in the README\n",
+    )
+    .replace(
+      "\n### Changed",
+      "\n### Removed\n\n- the `--legacy` flag\n\n### Changed",
+    );
+  write_fixture(dir.path(), "CHANGELOG.md", unreleased.as_bytes());
+
+  wucc_on_release_day(dir.path(), &args(["version", "-m", "--changelog"]));
+  assert_eq!(
+    mask_hashes(&read(dir.path(), "CHANGELOG.md")),
+    format!(
+      "{}\n{}### Removed\n\n- the `--legacy` flag\n\n",
+      HEADER,
+      RELEASE.replace(
+        "### Added\n\n",
+        "### Added\n\n- document the new flag\n  in the README\n"
+      )
+    )
+  );
+}
+
+#[test]
+fn regenerate_from_tags() {
+  let dir = with_commits();
+  git(dir.path(), &["tag", "v1.5.0"]);
+  write_fixture(dir.path(), "f.txt", b"f");
+  git(dir.path(), &["add", "f.txt"]);
+  git(
+    dir.path(),
+    &["commit", "--quiet", "-m", "fix(io): flush on exit"],
+  );
+
+  wucc_on_release_day(
+    dir.path(),
+    &args(["changelog", "--regenerate", "--unreleased"]),
+  );
+  let date = git(dir.path(), &["log", "-1", "--format=%cs", "v1.5.0"]);
+  let changelog = mask_hashes(&read(dir.path(), "CHANGELOG.md"));
+  let expected = format!(
+    "{}\n## [Unreleased]\n\n### Fixed\n\n- **io:** flush on exit (#######)\n\n{}## [1.4.2] - {}\n\n",
+    HEADER,
+    RELEASE.replace("2024-01-02", date.trim()),
+    date.trim()
+  );
+  assert_eq!(changelog, expected);
+}
+
+#[test]
+fn outside_a_repository() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/cargo", dir.path());
+
+  let output = run_wucc(dir.path(), &args(["changelog"]));
+  assert_eq!(output.status.code(), Some(10));
+  let output = run_wucc(dir.path(), &args(["version", "-p", "--changelog"]));
+  assert_eq!(output.status.code(), Some(10));
+  assert!(read(dir.path(), "Cargo.toml").contains("1.4.2"));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{args, write_fixture, wucc};
+
+#[test]
+fn embed_targets_the_requested_standard() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "shader.glsl", b"void main() {}\n");
+  write_fixture(root, "logo.png", &[0x89, 0x50, 0x4E, 0x47]);
+
+  for (standard, out) in [("c++14", "old"), ("c++20", "new")] {
+    wucc(
+      root,
+      &args([
+        "embed",
+        "-n",
+        "app::res",
+        "-o",
+        "res",
+        "-O",
+        out,
+        "--std",
+        standard,
+        "-t",
+        "shader.glsl",
+        "-b",
+        "logo.png",
+      ]),
+    );
+  }
+
+  let old = std::fs::read_to_string(root.join("old/res.rc.h")).unwrap();
+  assert!(old.contains("namespace app { namespace res {"));
+  assert!(old.contains("char const* shader_glsl() noexcept;"));
+  assert!(old.contains("::wucc::span_view<unsigned char> logo_png() noexcept;"));
+
+  let new = std::fs::read_to_string(root.join("new/res.rc.h")).unwrap();
+  assert!(new.contains("namespace app::res {"));
+  assert!(new.contains("std::string_view shader_glsl() noexcept;"));
+  assert!(new.contains("std::span<std::byte const> logo_png() noexcept;"));
+  assert!(!new.contains("span_view"));
+}
+
+#[test]
+fn include_guard_style_is_selectable() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "config.json", br#"{"a": 1}"#);
+
+  for guard in ["pragma", "ifndef"] {
+    wucc(
+      root,
+      &args([
+        "json-to-cpp",
+        "config.json",
+        "-n",
+        "app::data",
+        "-O",
+        guard,
+        "--include-guard",
+        guard,
+      ]),
+    );
+  }
+
+  let pragma = std::fs::read_to_string(root.join("pragma/config.json.h")).unwrap();
+  assert!(pragma.contains("#pragma once\n"));
+  assert!(!pragma.contains("#ifndef"));
+
+  let ifndef = std::fs::read_to_string(root.join("ifndef/config.json.h")).unwrap();
+  assert!(!ifndef.contains("#pragma once"));
+  assert!(ifndef.contains("#ifndef APP_DATA_CONFIG_JSON_H\n#define APP_DATA_CONFIG_JSON_H\n"));
+  assert!(
+    ifndef
+      .trim_end()
+      .ends_with("#endif // APP_DATA_CONFIG_JSON_H")
+  );
+}
@@ -0,0 +1,172 @@
+#![allow(dead_code)]
+
+use std::{
+  ffi::OsString,
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+/// Runs the `wucc` binary in `cwd` and returns its output.
+pub fn run_wucc(cwd: &Path, args: &[OsString]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(cwd)
+    .env("NO_COLOR", "1")
+    .args(args)
+    .output()
+    .expect("failed to run wucc")
+}
+
+/// Runs the `wucc` binary in `cwd` and asserts that it succeeds.
+pub fn wucc(cwd: &Path, args: &[OsString]) -> Output {
+  let output = run_wucc(cwd, args);
+  assert!(
+    output.status.success(),
+    "wucc {:?} failed:\n{}",
+    args,
+    String::from_utf8_lossy(&output.stderr)
+  );
+  output
+}
+
+pub fn args<I, S>(items: I) -> Vec<OsString>
+where
+  I: IntoIterator<Item = S>,
+  S: Into<OsString>,
+{
+  items.into_iter().map(Into::into).collect()
+}
+
+pub fn write_fixture(root: &Path, relative: &str, content: &[u8]) -> PathBuf {
+  let path = root.join(relative);
+  std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+  std::fs::write(&path, content).unwrap();
+  path
+}
+
+/// A temporary directory holding the `fixtures`, copied one after the other
+/// as [`copy_fixture`] does.
+pub fn project(fixtures: &[&str]) -> tempfile::TempDir {
+  let dir = tempfile::tempdir().unwrap();
+  for fixture in fixtures {
+    copy_fixture(fixture, dir.path());
+  }
+  dir
+}
+
+pub fn read(dir: &Path, file: &str) -> String {
+  std::fs::read_to_string(dir.join(file)).unwrap()
+}
+
+/// What `wucc version --show` prints in `dir`, with `extra` arguments.
+pub fn shown(dir: &Path, extra: &[&str]) -> String {
+  let mut arguments = vec!["version", "--show"];
+  arguments.extend(extra);
+  String::from_utf8(wucc(dir, &args(arguments)).stdout).unwrap()
+}
+
+pub fn manifest_dir() -> &'static Path {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Copies `tests/fixtures/<fixture>` into `dest`, dropping the `.fixture`
+/// suffix that keeps version files from being picked up inside this repository.
+pub fn copy_fixture(fixture: &str, dest: &Path) {
+  fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+      let path = entry.unwrap().path();
+      let name = path.file_name().unwrap().to_string_lossy().to_string();
+      let target = to.join(name.strip_suffix(".fixture").unwrap_or(&name));
+      if path.is_dir() {
+        copy_dir(&path, &target);
+      } else {
+        std::fs::copy(&path, &target).unwrap();
+      }
+    }
+  }
+
+  copy_dir(&manifest_dir().join("tests/fixtures").join(fixture), dest);
+}
+
+/// Compares `actual` with the committed golden file `tests/golden/<name>`.
+///
+/// Run the tests with `UPDATE_SNAPSHOTS=1` to write the current output as the
+/// new golden file instead, then review the diff before committing it.
+pub fn assert_golden(name: &str, actual: &str) {
+  let path = manifest_dir().join("tests/golden").join(name);
+  if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, actual).unwrap();
+    return;
+  }
+
+  let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+    panic!(
+      "golden file {} is missing; run with UPDATE_SNAPSHOTS=1 to create it",
+      path.display()
+    )
+  });
+  if expected == actual {
+    return;
+  }
+
+  let mismatch = expected
+    .lines()
+    .zip(actual.lines())
+    .position(|(e, a)| e != a)
+    .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+  panic!(
+    "output differs from golden file {} at line {}:\n  expected: {:?}\n  actual:   {:?}\n\
+     run with UPDATE_SNAPSHOTS=1 to accept the new output",
+    path.display(),
+    mismatch + 1,
+    expected.lines().nth(mismatch).unwrap_or("<end of file>"),
+    actual.lines().nth(mismatch).unwrap_or("<end of file>"),
+  );
+}
+
+/// Runs git in `dir`, asserts that it succeeds and returns its stdout.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+  let output = Command::new("git")
+    .current_dir(dir)
+    .args(args)
+    .output()
+    .expect("failed to run git");
+  assert!(
+    output.status.success(),
+    "git {:?} failed:\n{}",
+    args,
+    String::from_utf8_lossy(&output.stderr)
+  );
+  String::from_utf8(output.stdout).unwrap()
+}
+
+/// Turns `dir` into a git repository with everything in it committed, using
+/// a local identity and no signing so the host configuration does not matter.
+pub fn init_repository(dir: &Path) {
+  git(dir, &["init", "--quiet"]);
+  for (key, value) in [
+    ("user.name", "Fixture"),
+    ("user.email", "fixture@example.com"),
+    ("commit.gpgSign", "false"),
+    ("tag.gpgSign", "false"),
+  ] {
+    git(dir, &["config", key, value]);
+  }
+  git(dir, &["add", "--all"]);
+  git(dir, &["commit", "--quiet", "-m", "Initial commit"]);
+}
+
+/// A git repository holding the `fixtures`, all committed.
+pub fn repository(fixtures: &[&str]) -> tempfile::TempDir {
+  let dir = project(fixtures);
+  init_repository(dir.path());
+  dir
+}
+
+/// Writes `message` to `file` and commits it with that message.
+pub fn commit(dir: &Path, file: &str, message: &str) {
+  write_fixture(dir, file, message.as_bytes());
+  git(dir, &["add", file]);
+  git(dir, &["commit", "--quiet", "-m", message]);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{args, copy_fixture, run_wucc, write_fixture};
+use std::process::Output;
+use wucc::{
+  Error,
+  version::{ExportHeaderFile, VersionIO},
+};
+
+fn stderr(output: &Output) -> String {
+  String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn missing_input_is_an_io_error() {
+  let dir = tempfile::tempdir().unwrap();
+  let output = run_wucc(
+    dir.path(),
+    &args(["json-to-cpp", "missing.json", "-n", "app", "-O", "out"]),
+  );
+
+  assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+  assert!(stderr(&output).contains("error: failed to access missing.json"));
+  assert!(stderr(&output).contains("caused by:"));
+}
+
+#[test]
+fn malformed_input_is_a_parse_error() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(dir.path(), "config.yml", b"key: [unclosed");
+  let output = run_wucc(
+    dir.path(),
+    &args(["yaml-to-cpp", "config.yml", "-n", "app", "-O", "out"]),
+  );
+
+  assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+}
+
+#[test]
+fn unusable_names_are_rejected() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(dir.path(), "1-config.json", b"{}");
+  write_fixture(dir.path(), "config.json", b"{}");
+
+  let output = run_wucc(
+    dir.path(),
+    &args(["json-to-cpp", "1-config.json", "-n", "app", "-O", "out"]),
+  );
+  assert_eq!(output.status.code(), Some(7), "{}", stderr(&output));
+  assert!(stderr(&output).contains("'1-config' from file 1-config.json"));
+
+  let output = run_wucc(
+    dir.path(),
+    &args(["json-to-cpp", "config.json", "-n", "app::2d", "-O", "out"]),
+  );
+  assert_eq!(output.status.code(), Some(7), "{}", stderr(&output));
+
+  let output = run_wucc(
+    dir.path(),
+    &args(["json-to-cpp", "config.json", "-n", "", "-O", "out"]),
+  );
+  assert_eq!(output.status.code(), Some(7), "{}", stderr(&output));
+  assert!(!dir.path().join("out").exists());
+}
+
+#[test]
+fn version_write_failures_are_reported() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/cargo", dir.path());
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"project(Fixture LANGUAGES CXX)\n",
+  );
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert_eq!(output.status.code(), Some(5), "{}", stderr(&output));
+  assert!(String::from_utf8_lossy(&output.stdout).contains("not written"));
+  assert!(stderr(&output).contains("failed to update 1 version file(s): CMakeLists"));
+
+  // The files that could be updated still are.
+  let manifest = std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+  assert!(manifest.contains("version = \"2.0.0\""));
+}
+
+#[test]
+fn unwritable_version_file_is_an_io_error() {
+  let dir = tempfile::tempdir().unwrap();
+  std::fs::create_dir(dir.path().join("CMakeLists.txt")).unwrap();
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+  assert!(stderr(&output).contains("CMakeLists: failed to access CMakeLists.txt"));
+}
+
+#[test]
+fn ambiguous_header_prefix_is_reported() {
+  let dir = tempfile::tempdir().unwrap();
+  let header = write_fixture(
+    dir.path(),
+    "version.h",
+    b"#define FOO_VERSION_MAJOR 1\n#define FOO_VERSION_MINOR 0\n#define FOO_VERSION_PATCH 0\n\
+      #define BAR_VERSION_MAJOR 2\n#define BAR_VERSION_MINOR 0\n#define BAR_VERSION_PATCH 0\n",
+  );
+
+  match ExportHeaderFile::new(&header).read() {
+    Err(error @ Error::AmbiguousPrefix { .. }) => {
+      assert_eq!(error.exit_code(), 6);
+      assert!(error.to_string().ends_with("BAR, FOO"), "{}", error);
+    }
+    other => panic!("expected an ambiguous prefix, got {:?}", other),
+  }
+}
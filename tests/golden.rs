@@ -0,0 +1,143 @@
+mod common;
+
+use common::{args, assert_golden, manifest_dir, read, wucc};
+
+#[test]
+fn json_headers() {
+  let out = tempfile::tempdir().unwrap();
+  let cases: [(&str, &[&str]); 3] = [
+    ("default", &[]),
+    ("nlohmann", &["--nlohmann"]),
+    (
+      "cpp11_ifndef",
+      &["--std", "c++11", "--include-guard", "ifndef"],
+    ),
+  ];
+
+  for (case, extra) in cases {
+    let dir = out.path().join(case);
+    let mut cmd = args([
+      "json-to-cpp",
+      "test_data/example.json",
+      "-n",
+      "wucc::golden",
+      "-O",
+    ]);
+    cmd.push(dir.clone().into());
+    cmd.extend(args(extra));
+    wucc(manifest_dir(), &cmd);
+
+    assert_golden(
+      &format!("json/{}/example.json.h", case),
+      &read(&dir, "example.json.h"),
+    );
+  }
+}
+
+#[test]
+fn yaml_headers() {
+  let out = tempfile::tempdir().unwrap();
+  let cases: [(&str, &[&str]); 2] = [
+    ("nlohmann", &["--nlohmann"]),
+    (
+      "cpp14_ifndef",
+      &["--std", "c++14", "--include-guard", "ifndef"],
+    ),
+  ];
+
+  for (case, extra) in cases {
+    let dir = out.path().join(case);
+    let mut cmd = args([
+      "yaml-to-cpp",
+      "test_data/example.yml",
+      "-n",
+      "wucc::golden",
+      "-O",
+    ]);
+    cmd.push(dir.clone().into());
+    cmd.extend(args(extra));
+    wucc(manifest_dir(), &cmd);
+
+    assert_golden(
+      &format!("yaml/{}/example.yml.h", case),
+      &read(&dir, "example.yml.h"),
+    );
+  }
+}
+
+#[test]
+fn embedded_resources() {
+  let out = tempfile::tempdir().unwrap();
+  let cases: [(&str, &[&str]); 3] = [
+    ("cpp20", &[]),
+    ("cpp17", &["--std", "c++17"]),
+    (
+      "cpp11_ifndef",
+      &["--std", "c++11", "--include-guard", "ifndef"],
+    ),
+  ];
+
+  for (case, extra) in cases {
+    let dir = out.path().join(case);
+    let mut cmd = args(["embed", "-n", "wucc::golden", "-o", "resources", "-O"]);
+    cmd.push(dir.clone().into());
+    cmd.extend(args(extra));
+    cmd.extend(args([
+      "-t",
+      "tests/fixtures/embed/greeting.txt",
+      "-b",
+      "tests/fixtures/embed/blob.bin",
+      "tests/fixtures/embed/logo.png",
+    ]));
+    wucc(manifest_dir(), &cmd);
+
+    for file in ["resources.rc.h", "resources.rc.cc"] {
+      assert_golden(&format!("embed/{}/{}", case, file), &read(&dir, file));
+    }
+  }
+}
+
+#[test]
+fn hexdump_templates() {
+  let out = tempfile::tempdir().unwrap();
+
+  for template in ["c", "cpp", "rust", "python"] {
+    let file = out.path().join(template);
+    let mut cmd = args(["hexdump", "-o"]);
+    cmd.push(file.clone().into());
+    cmd.extend(args([
+      "generate",
+      "-t",
+      template,
+      "tests/fixtures/embed/logo.png",
+    ]));
+    wucc(manifest_dir(), &cmd);
+
+    assert_golden(
+      &format!("hexdump/generate.{}", template),
+      &read(out.path(), template),
+    );
+  }
+}
+
+#[test]
+fn hexdump_dumps() {
+  let out = tempfile::tempdir().unwrap();
+  let cases: [(&str, &[&str]); 3] = [
+    ("hex", &[]),
+    ("plain", &["-p"]),
+    ("bin_grouped", &["-f", "bin", "-g", "1", "-c", "4"]),
+  ];
+
+  for (case, extra) in cases {
+    let file = out.path().join(case);
+    let mut cmd = args(["hexdump", "-o"]);
+    cmd.push(file.clone().into());
+    cmd.push("dump".into());
+    cmd.extend(args(extra));
+    cmd.push("tests/fixtures/embed/logo.png".into());
+    wucc(manifest_dir(), &cmd);
+
+    assert_golden(&format!("hexdump/dump.{}", case), &read(out.path(), case));
+  }
+}
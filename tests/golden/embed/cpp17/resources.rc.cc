@@ -0,0 +1,76 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - tests/fixtures/embed/greeting.txt
+ *   - tests/fixtures/embed/blob.bin
+ *   - tests/fixtures/embed/logo.png
+ *
+ * Header: resources.rc.h
+ * Source: resources.rc.cc
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+
+#include "resources.rc.h"
+
+#include <array>
+#include <cstddef>
+#include <cstdint>
+#include <string_view>
+
+namespace wucc::golden {
+  namespace {
+  
+    auto constexpr greeting_txt_c_str = R"WUCC_DELIMITER(Hello, "wucc"!
+Second line
+)WUCC_DELIMITER";
+  
+  
+    auto constexpr blob_bin_data = std::array<std::uint8_t const, 6>{
+      0x00,
+      0x01,
+      0x7F,
+      0x80,
+      0xFE,
+      0xFF
+    };
+  
+    auto constexpr logo_png_data = std::array<std::uint8_t const, 16>{
+      0x89,
+      0x50,
+      0x4E,
+      0x47,
+      0x0D,
+      0x0A,
+      0x1A,
+      0x0A,
+      0x00,
+      0x00,
+      0x00,
+      0x0D,
+      0x49,
+      0x48,
+      0x44,
+      0x52
+    };
+  
+  } // namespace
+  
+    std::string_view greeting_txt() noexcept {
+      return greeting_txt_c_str;
+    }
+  
+  
+    ::wucc::span_view<std::byte> blob_bin() noexcept {
+      return { reinterpret_cast<std::byte const*>(blob_bin_data.data()), 6 };
+    }
+  
+    ::wucc::span_view<std::byte> logo_png() noexcept {
+      return { reinterpret_cast<std::byte const*>(logo_png_data.data()), 16 };
+    }
+  
+} // namespace wucc::golden
\ No newline at end of file
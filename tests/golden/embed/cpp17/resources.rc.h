@@ -0,0 +1,53 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - tests/fixtures/embed/greeting.txt
+ *   - tests/fixtures/embed/blob.bin
+ *   - tests/fixtures/embed/logo.png
+ *
+ * Header: resources.rc.h
+ * Source: resources.rc.cc
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+#pragma once
+
+#include <cstddef>
+#include <string_view>
+
+#ifndef WUCC_SPAN_VIEW_DEFINED
+#define WUCC_SPAN_VIEW_DEFINED
+namespace wucc {
+  template <typename T>
+  struct span_view {
+    T const* ptr;
+    std::size_t count;
+
+    T const* data() const noexcept { return ptr; }
+    std::size_t size() const noexcept { return count; }
+    T const* begin() const noexcept { return ptr; }
+    T const* end() const noexcept { return ptr + count; }
+  };
+} // namespace wucc
+#endif // WUCC_SPAN_VIEW_DEFINED
+
+namespace wucc::golden {
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    std::string_view greeting_txt() noexcept;
+  
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    ::wucc::span_view<std::byte> blob_bin() noexcept;
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    ::wucc::span_view<std::byte> logo_png() noexcept;
+  
+} // namespace wucc::golden
\ No newline at end of file
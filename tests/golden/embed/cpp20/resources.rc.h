@@ -0,0 +1,38 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - tests/fixtures/embed/greeting.txt
+ *   - tests/fixtures/embed/blob.bin
+ *   - tests/fixtures/embed/logo.png
+ *
+ * Header: resources.rc.h
+ * Source: resources.rc.cc
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+#pragma once
+
+#include <cstddef>
+#include <span>
+#include <string_view>
+
+namespace wucc::golden {
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    std::string_view greeting_txt() noexcept;
+  
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    std::span<std::byte const> blob_bin() noexcept;
+  
+    [[nodiscard]]
+    [[maybe_unused]]
+    std::span<std::byte const> logo_png() noexcept;
+  
+} // namespace wucc::golden
\ No newline at end of file
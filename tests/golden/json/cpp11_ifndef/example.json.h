@@ -0,0 +1,26 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - test_data/example.json
+ *
+ * File stem: example
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+#ifndef WUCC_GOLDEN_EXAMPLE_JSON_H
+#define WUCC_GOLDEN_EXAMPLE_JSON_H
+
+
+namespace wucc { namespace golden {
+  
+  inline namespace example {
+    auto constexpr example_c_str = "{\n  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n  \"type\": \"object\",\n  \"properties\": {\n    \"uuid\": {\n      \"type\": \"string\"\n    },\n    \"name\": {\n      \"type\": \"string\"\n    },\n    \"version\": {\n      \"type\": \"string\"\n    },\n    \"description\": {\n      \"type\": \"string\"\n    },\n    \"domain\": {\n      \"type\": \"string\"\n    },\n    \"organization\": {\n      \"type\": \"string\"\n    },\n    \"logger_id\": {\n      \"type\": \"string\"\n    },\n    \"quick_view\": {\n      \"type\": \"string\"\n    },\n    \"icon\": {\n      \"type\": \"string\"\n    },\n    \"banner\": {\n      \"type\": \"string\"\n    },\n    \"dependencies\": {\n      \"type\": \"array\",\n      \"items\": {\n        \"type\": \"string\"\n      }\n    }\n  },\n  \"required\": [\n    \"uuid\",\n    \"name\",\n    \"version\"\n  ]\n}";
+  } // namespace example
+  
+} } // namespace wucc::golden
+
+#endif // WUCC_GOLDEN_EXAMPLE_JSON_H
\ No newline at end of file
@@ -0,0 +1,29 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - test_data/example.json
+ *
+ * File stem: example
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+#pragma once
+
+
+#include <nlohmann/json.hpp>
+
+namespace wucc::golden {
+  
+  inline namespace example {
+    [[maybe_unused]]
+    inline auto constexpr example_c_str = "{\n  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n  \"type\": \"object\",\n  \"properties\": {\n    \"uuid\": {\n      \"type\": \"string\"\n    },\n    \"name\": {\n      \"type\": \"string\"\n    },\n    \"version\": {\n      \"type\": \"string\"\n    },\n    \"description\": {\n      \"type\": \"string\"\n    },\n    \"domain\": {\n      \"type\": \"string\"\n    },\n    \"organization\": {\n      \"type\": \"string\"\n    },\n    \"logger_id\": {\n      \"type\": \"string\"\n    },\n    \"quick_view\": {\n      \"type\": \"string\"\n    },\n    \"icon\": {\n      \"type\": \"string\"\n    },\n    \"banner\": {\n      \"type\": \"string\"\n    },\n    \"dependencies\": {\n      \"type\": \"array\",\n      \"items\": {\n        \"type\": \"string\"\n      }\n    }\n  },\n  \"required\": [\n    \"uuid\",\n    \"name\",\n    \"version\"\n  ]\n}";
+
+    [[maybe_unused]]
+    static inline auto example_json = nlohmann::json::parse(example_c_str);
+  } // namespace example
+  
+} // namespace wucc::golden
\ No newline at end of file
@@ -0,0 +1,29 @@
+/*
+ * Generated by the whs31's ucc compiler (wucc)
+ * Source file(s): 
+ *   - test_data/example.yml
+ *
+ * File stem: example
+ *
+ * Copyright (c) 2025 whs31
+ * https://github.com/whs31/wucc
+ * https://crate.io/crates/wucc
+ *
+ * DO NOT MODIFY. THIS FILE IS AUTO-GENERATED BY THE BUILD SYSTEM
+ */
+#pragma once
+
+
+#include <nlohmann/json.hpp>
+
+namespace wucc::golden {
+  
+  inline namespace example {
+    [[maybe_unused]]
+    inline auto constexpr example_c_str = "{\"uuid\":\"12b5e0f0-6d83-11d0-94ab-0080c74c7e95\",\"name\":\"Corona\",\"version\":\"2.9.21\",\"description\":\"Интерфейс управления РЛС \\\"Квазар\\\" и система просмотра, обработки и анализа РЛИ\",\"domain\":\"com.whs31.quasar.corona\",\"organization\":\"whs31\",\"logger_id\":\"corona\",\"quick_view\":\"qrc:/corona/Entry.qml\",\"icon\":\"qrc:/corona/wireframe_icon.svg\"}";
+
+    [[maybe_unused]]
+    static inline auto example_json = nlohmann::json::parse(example_c_str);
+  } // namespace example
+  
+} // namespace wucc::golden
\ No newline at end of file
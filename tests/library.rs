@@ -0,0 +1,67 @@
+mod common;
+
+use common::{assert_golden, copy_fixture};
+use std::path::PathBuf;
+use wucc::{
+  Error,
+  compilers::{embed::EmbedCompiler, json::JsonCompiler},
+  version::{CargoFile, VersionIO},
+};
+
+#[test]
+fn embed_compiler_matches_cli_output() {
+  let out = tempfile::tempdir().unwrap();
+
+  let (header, source) = EmbedCompiler::new("wucc::golden".to_string(), out.path())
+    .compile(
+      &[PathBuf::from("tests/fixtures/embed/greeting.txt")],
+      &[
+        PathBuf::from("tests/fixtures/embed/blob.bin"),
+        PathBuf::from("tests/fixtures/embed/logo.png"),
+      ],
+      &Some("resources".to_string()),
+    )
+    .unwrap();
+
+  assert_golden(
+    "embed/cpp20/resources.rc.h",
+    &std::fs::read_to_string(header).unwrap(),
+  );
+  assert_golden(
+    "embed/cpp20/resources.rc.cc",
+    &std::fs::read_to_string(source).unwrap(),
+  );
+}
+
+#[test]
+fn compiler_errors_are_typed() {
+  let dir = tempfile::tempdir().unwrap();
+  let invalid = dir.path().join("invalid.json");
+  std::fs::write(&invalid, "{ not json").unwrap();
+  let compiler = JsonCompiler::new("app".to_string(), dir.path(), false);
+
+  match compiler.compile(std::slice::from_ref(&invalid), &None) {
+    Err(Error::Json { path, .. }) => assert_eq!(path, invalid),
+    other => panic!("expected a JSON error, got {:?}", other),
+  }
+
+  let missing = dir.path().join("missing.json");
+  match compiler.compile(std::slice::from_ref(&missing), &None) {
+    Err(Error::Io { path, .. }) => assert_eq!(path, missing),
+    other => panic!("expected an I/O error, got {:?}", other),
+  }
+}
+
+#[test]
+fn version_files_are_usable_directly() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/cargo", dir.path());
+  let cargo = CargoFile::new(&dir.path().join("Cargo.toml"));
+
+  assert_eq!(cargo.read().unwrap(), semver::Version::new(1, 4, 2));
+  cargo.write(&semver::Version::new(2, 0, 0)).unwrap();
+  assert_eq!(cargo.read().unwrap(), semver::Version::new(2, 0, 0));
+
+  let missing = CargoFile::new(&dir.path().join("missing/Cargo.toml"));
+  assert!(matches!(missing.read(), Err(Error::Io { .. })));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::{args, write_fixture, wucc};
+
+#[test]
+fn json_output_does_not_depend_on_argument_order() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  let a = write_fixture(root, "config/a.json", br#"{"a": 1}"#);
+  let b = write_fixture(root, "config/b.json", br#"{"b": 2}"#);
+
+  for (out, inputs) in [("out1", [&a, &b]), ("out2", [&b, &a])] {
+    let mut cmd = args([
+      "json-to-cpp",
+      "-n",
+      "test::ns",
+      "--nlohmann",
+      "-o",
+      "config",
+      "--source-root",
+    ]);
+    cmd.push(root.into());
+    cmd.push("-O".into());
+    cmd.push(root.join(out).into());
+    cmd.extend(inputs.iter().map(|p| p.as_os_str().to_owned()));
+    wucc(root, &cmd);
+  }
+
+  let first = std::fs::read(root.join("out1/config.json.h")).unwrap();
+  let second = std::fs::read(root.join("out2/config.json.h")).unwrap();
+  assert_eq!(first, second);
+
+  // Without a name, the output is named after the inputs as given.
+  let mut cmd = args(["json-to-cpp", "-n", "test::ns", "-O", "out3"]);
+  cmd.extend([&b, &a].iter().map(|p| p.as_os_str().to_owned()));
+  wucc(root, &cmd);
+  assert!(root.join("out3/b_a.json.h").exists());
+
+  let header = String::from_utf8(first).unwrap();
+  assert!(header.contains(" *   - config/a.json\n *   - config/b.json\n"));
+}
+
+#[test]
+fn embed_output_does_not_depend_on_invocation() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  let first_text = write_fixture(root, "assets/text/first.txt", b"first");
+  let second_text = write_fixture(root, "assets/text/second.txt", b"second");
+  let first_bin = write_fixture(root, "assets/bin/first.bin", &[0x00, 0xFF]);
+  let second_bin = write_fixture(root, "assets/bin/second.bin", &[0x7F]);
+
+  let mut absolute = args(["embed", "-n", "test", "-o", "res", "--source-root"]);
+  absolute.push(root.into());
+  absolute.push("-O".into());
+  absolute.push(root.join("out1").into());
+  absolute.extend(args(["-t"]));
+  absolute.extend(args([&first_text, &second_text]));
+  absolute.extend(args(["-b"]));
+  absolute.extend(args([&first_bin, &second_bin]));
+  wucc(root, &absolute);
+
+  let relative = args([
+    "embed",
+    "-n",
+    "test",
+    "-o",
+    "res",
+    "--source-root",
+    ".",
+    "-O",
+    "out2",
+    "-t",
+    "./assets/text/second.txt",
+    "assets/text/first.txt",
+    "-b",
+    "assets/bin/second.bin",
+    "./assets/bin/first.bin",
+  ]);
+  wucc(root, &relative);
+
+  for file in ["res.rc.h", "res.rc.cc"] {
+    let first = std::fs::read_to_string(root.join("out1").join(file)).unwrap();
+    let second = std::fs::read_to_string(root.join("out2").join(file)).unwrap();
+    assert_eq!(first, second, "{} differs between invocations", file);
+    assert!(!first.contains('\\'), "{} contains a backslash", file);
+    assert!(
+      !first.contains(&*root.to_string_lossy()),
+      "{} leaks the absolute source root",
+      file
+    );
+  }
+
+  let header = std::fs::read_to_string(root.join("out1/res.rc.h")).unwrap();
+  assert!(header.contains(" *   - assets/text/first.txt\n *   - assets/text/second.txt\n"));
+  assert!(header.contains(" *   - assets/bin/first.bin\n *   - assets/bin/second.bin\n"));
+}
+
+#[test]
+fn paths_outside_source_root_are_kept_as_given() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "data/meta.yml", b"version: 1.0.0\n");
+  write_fixture(root, "other/sub/placeholder", b"");
+
+  let cmd = args([
+    "yaml-to-cpp",
+    "-n",
+    "test",
+    "--source-root",
+    "other/sub",
+    "-O",
+    "out",
+    "./data/meta.yml",
+  ]);
+  wucc(root, &cmd);
+
+  let header = std::fs::read_to_string(root.join("out/meta.yml.h")).unwrap();
+  assert!(header.contains(" *   - data/meta.yml\n"));
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use common::{args, run_wucc, write_fixture, wucc};
+use std::{ffi::OsString, path::Path};
+
+const STANDARDS: [&str; 5] = ["c++11", "c++14", "c++17", "c++20", "c++23"];
+const GUARDS: [&str; 2] = ["pragma", "ifndef"];
+
+/// Set on hosts without a C++ compiler, where `--verify` can only skip.
+const SKIP_VARIABLE: &str = "WUCC_SKIP_VERIFY_TESTS";
+
+/// Runs `wucc` with `--verify` and asserts that the generated files were
+/// actually compiled, unless `WUCC_SKIP_VERIFY_TESTS` is set.
+fn verified(cwd: &Path, args: &[OsString]) {
+  let output = wucc(cwd, args);
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  if stdout.contains("Skipped verification") {
+    assert!(
+      std::env::var_os(SKIP_VARIABLE).is_some(),
+      "no C++ compiler found to verify the generated sources; install one, set CXX, \
+       or set {}=1 to skip these tests",
+      SKIP_VARIABLE
+    );
+    return;
+  }
+  assert!(
+    stdout.contains("Verified"),
+    "nothing was verified:\n{}",
+    stdout
+  );
+}
+
+#[test]
+fn generated_embed_sources_are_self_contained() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "shader.glsl", b"void main() {}\n");
+  write_fixture(root, "logo.png", &[0x89, 0x50, 0x4E, 0x47, 0x00]);
+
+  for standard in STANDARDS {
+    for guard in GUARDS {
+      let out = format!("out-{}-{}", standard, guard);
+      verified(
+        root,
+        &args([
+          "embed",
+          "-n",
+          "app::resources",
+          "-o",
+          "res",
+          "-O",
+          &out,
+          "--std",
+          standard,
+          "--include-guard",
+          guard,
+          "--verify",
+          "-t",
+          "shader.glsl",
+          "-b",
+          "logo.png",
+        ]),
+      );
+    }
+  }
+}
+
+#[test]
+fn generated_json_headers_are_self_contained() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(
+    root,
+    "config.json",
+    br#"{"name": "wucc", "tags": ["a", "b"]}"#,
+  );
+  write_fixture(root, "meta.yml", b"name: wucc\nversion: 1.0.0\n");
+
+  for standard in STANDARDS {
+    for guard in GUARDS {
+      let out = format!("out-{}-{}", standard, guard);
+      let options = [
+        "-n",
+        "app::data",
+        "-O",
+        &out,
+        "--std",
+        standard,
+        "--include-guard",
+        guard,
+        "--verify",
+      ];
+      verified(
+        root,
+        &args(["json-to-cpp", "config.json"].iter().chain(&options)),
+      );
+      verified(
+        root,
+        &args(["yaml-to-cpp", "meta.yml"].iter().chain(&options)),
+      );
+    }
+  }
+}
+
+#[test]
+fn verification_failures_are_reported() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "config.json", b"{}");
+
+  let output = std::process::Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(root)
+    .env("CXX", "false")
+    .args([
+      "json-to-cpp",
+      "config.json",
+      "-n",
+      "app",
+      "-O",
+      "out",
+      "--verify",
+    ])
+    .output()
+    .unwrap();
+
+  assert!(!output.status.success());
+  assert!(String::from_utf8_lossy(&output.stderr).contains("failed to compile"));
+}
+
+#[test]
+fn compiler_command_takes_arguments() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "config.json", b"{}");
+
+  // Like `ccache g++`: the program is the first word of `CXX`.
+  let output = std::process::Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(root)
+    .env("NO_COLOR", "1")
+    .env("CXX", "true --ignored")
+    .args([
+      "json-to-cpp",
+      "config.json",
+      "-n",
+      "app",
+      "-O",
+      "out",
+      "--verify",
+    ])
+    .output()
+    .unwrap();
+
+  assert!(
+    output.status.success(),
+    "{}",
+    String::from_utf8_lossy(&output.stderr)
+  );
+  assert!(String::from_utf8_lossy(&output.stdout).contains("Verified"));
+}
+
+#[test]
+fn verification_is_opt_in() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  write_fixture(root, "config.json", b"{}");
+
+  let output = run_wucc(
+    root,
+    &args(["json-to-cpp", "config.json", "-n", "app", "-O", "out"]),
+  );
+  assert!(output.status.success());
+  assert!(!String::from_utf8_lossy(&output.stdout).contains("Verified"));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{args, commit, git, read, repository, wucc};
+use std::path::Path;
+use wucc::version::{Bump, ConventionalCommit, bump_level};
+
+fn auto_version(dir: &Path) -> String {
+  let output = wucc(dir, &args(["version", "--auto", "--get"]));
+  String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn parse_messages() {
+  let commit = ConventionalCommit::parse("feat(parser)!: accept tabs\n\nDetails.").unwrap();
+  assert_eq!(commit.kind, "feat");
+  assert_eq!(commit.scope.as_deref(), Some("parser"));
+  assert!(commit.breaking);
+  assert_eq!(commit.description, "accept tabs");
+
+  let commit = ConventionalCommit::parse("fix: typo\n\nBREAKING CHANGE: renamed").unwrap();
+  assert_eq!(commit.bump(), Some(Bump::Major));
+  assert_eq!(ConventionalCommit::parse("Update README"), None);
+
+  assert_eq!(
+    bump_level(["docs: x", "fix: y", "feat: z"]),
+    Some(Bump::Minor)
+  );
+  assert_eq!(bump_level(["docs: x", "Merge branch 'main'"]), None);
+}
+
+#[test]
+fn bump_level_follows_commits() {
+  let dir = repository(&["version/cargo", "version/cmake"]);
+  git(dir.path(), &["tag", "v1.4.2"]);
+  commit(dir.path(), "a.txt", "fix: handle empty files");
+  assert_eq!(auto_version(dir.path()), "1.4.3\n");
+  git(
+    dir.path(),
+    &["commit", "--quiet", "-am", "chore: release 1.4.3"],
+  );
+  git(dir.path(), &["tag", "v1.4.3"]);
+
+  commit(dir.path(), "b.txt", "feat(cli): add --auto");
+  commit(dir.path(), "c.txt", "docs: mention --auto");
+  assert_eq!(auto_version(dir.path()), "1.5.0\n");
+  git(
+    dir.path(),
+    &["commit", "--quiet", "-am", "chore: release 1.5.0"],
+  );
+  git(dir.path(), &["tag", "v1.5.0"]);
+
+  commit(dir.path(), "d.txt", "refactor!: drop the old syntax");
+  let output = wucc(dir.path(), &args(["version", "--auto"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(
+    stdout.contains("Detected major bump from 1 commit(s) since v1.5.0"),
+    "{}",
+    stdout
+  );
+  assert!(read(dir.path(), "Cargo.toml").contains("version = \"2.0.0\""));
+}
+
+#[test]
+fn nothing_to_release() {
+  let dir = repository(&["version/cargo", "version/cmake"]);
+  git(dir.path(), &["tag", "v1.4.2"]);
+  commit(dir.path(), "a.txt", "docs: explain the fixture");
+
+  let output = wucc(dir.path(), &args(["version", "--auto"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("nothing to release"), "{}", stdout);
+  assert!(git(dir.path(), &["status", "--porcelain"]).is_empty());
+  assert_eq!(auto_version(dir.path()), "1.4.2\n");
+}
+
+#[test]
+fn without_tags_all_commits_count() {
+  let dir = repository(&["version/cargo", "version/cmake"]);
+  git(
+    dir.path(),
+    &["commit", "--quiet", "--amend", "-m", "feat: initial import"],
+  );
+  assert_eq!(auto_version(dir.path()), "1.5.0\n");
+}
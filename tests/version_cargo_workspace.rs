@@ -0,0 +1,59 @@
+mod common;
+
+use common::{args, assert_golden, project, read, wucc};
+
+/// Files of the workspace fixture that hold its version.
+const FILES: [&str; 4] = [
+  "Cargo.toml",
+  "Cargo.lock",
+  "crates/core/Cargo.toml",
+  "crates/cli/Cargo.toml",
+];
+
+#[test]
+fn members_are_detected() {
+  let dir = project(&["version/cargo-workspace"]);
+  let output = wucc(dir.path(), &args(["version", "--show"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  assert_eq!(
+    stdout,
+    "• Cargo manifest........... 1.4.2\n\
+     • Cargo manifest (fixture-cli) 1.4.2\n\
+     • Cargo manifest (fixture-core) 1.4.2\n\
+     • Cargo lockfile........... 1.4.2\n"
+  );
+}
+
+#[test]
+fn bump_updates_members_requirements_and_lockfile() {
+  let dir = project(&["version/cargo-workspace"]);
+  let experimental = read(dir.path(), "crates/experimental/Cargo.toml");
+
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+
+  for file in FILES {
+    assert_golden(
+      &format!("version/cargo-workspace/{}.golden", file),
+      &read(dir.path(), file),
+    );
+  }
+  assert_eq!(
+    read(dir.path(), "crates/experimental/Cargo.toml"),
+    experimental
+  );
+  wucc(dir.path(), &args(["version", "--check"]));
+}
+
+#[test]
+fn unchanged_version_leaves_the_workspace_alone() {
+  let dir = project(&["version/cargo-workspace"]);
+  let original: Vec<String> = FILES.iter().map(|file| read(dir.path(), file)).collect();
+
+  wucc(dir.path(), &args(["version", "--assign", "1.4.2"]));
+
+  let after: Vec<String> = FILES.iter().map(|file| read(dir.path(), file)).collect();
+  // Only the `^1.4` requirement is spelled differently afterwards.
+  assert_eq!(after[..3], original[..3]);
+  assert_eq!(after[3], original[3].replace("\"^1.4\"", "\"^1.4.2\""));
+}
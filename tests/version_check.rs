@@ -0,0 +1,90 @@
+mod common;
+
+use common::{args, project, run_wucc, wucc};
+use std::path::Path;
+
+fn bump_cargo_only(dir: &Path) {
+  let path = dir.join("Cargo.toml");
+  let manifest = std::fs::read_to_string(&path).unwrap();
+  std::fs::write(&path, manifest.replace("1.4.2", "1.5.0")).unwrap();
+}
+
+#[test]
+fn synced_versions_pass() {
+  let dir = project(&["version/cargo", "version/conan", "version/cmake"]);
+  let output = wucc(dir.path(), &args(["version", "--check"]));
+
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout.lines().count(), 3, "{}", stdout);
+  assert!(!stdout.contains("expected"));
+}
+
+#[test]
+fn drift_fails_against_the_highest_version() {
+  let dir = project(&["version/cargo", "version/conan", "version/cmake"]);
+  bump_cargo_only(dir.path());
+
+  let output = run_wucc(dir.path(), &args(["version", "--check"]));
+  assert_eq!(output.status.code(), Some(9));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("CMakeLists............... 1.4.2 (expected 1.5.0)"));
+  assert!(stdout.contains("Conanfile................ 1.4.2 (expected 1.5.0)"));
+  assert!(
+    String::from_utf8_lossy(&output.stderr).contains(
+      "2 version file(s) out of sync with highest version (1.5.0): CMakeLists, Conanfile"
+    )
+  );
+}
+
+#[test]
+fn drift_fails_against_a_reference_file() {
+  let dir = project(&["version/cargo", "version/conan", "version/cmake"]);
+  bump_cargo_only(dir.path());
+
+  let output = run_wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--check",
+      "--reference",
+      "conan",
+      "--format",
+      "json",
+    ]),
+  );
+  assert_eq!(output.status.code(), Some(9));
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(report["version"], "1.4.2");
+  assert_eq!(report["reference"], "conan");
+  let statuses: Vec<(&str, &str)> = report["files"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|file| {
+      (
+        file["kind"].as_str().unwrap(),
+        file["status"].as_str().unwrap(),
+      )
+    })
+    .collect();
+  assert_eq!(
+    statuses,
+    [
+      ("cargo", "out-of-sync"),
+      ("cmake", "in-sync"),
+      ("conan", "in-sync")
+    ]
+  );
+}
+
+#[test]
+fn missing_reference_is_not_found() {
+  let dir = project(&["version/cargo", "version/conan", "version/cmake"]);
+  let output = run_wucc(
+    dir.path(),
+    &args(["version", "--check", "-r", "lua-metafile"]),
+  );
+
+  assert_eq!(output.status.code(), Some(5));
+  assert!(String::from_utf8_lossy(&output.stderr).contains("'lua-metafile' not detected"));
+}
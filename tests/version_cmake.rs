@@ -0,0 +1,94 @@
+mod common;
+
+use common::{args, read, run_wucc, shown, write_fixture, wucc};
+
+#[test]
+fn multi_line_project_is_updated() {
+  let dir = tempfile::tempdir().unwrap();
+  let content = "cmake_minimum_required(VERSION 3.20)\n\
+    \n\
+    #[[ project(Old VERSION 0.0.1) ]]\n\
+    project(\n  \
+      Fixture # the library\n  \
+      DESCRIPTION \"Mentions VERSION 9.9.9\"\n  \
+      VERSION\n    \
+        1.4.2\n  \
+      LANGUAGES CXX\n\
+    )\n\
+    \n\
+    if(BUILD_DOCS)\n  \
+      project(FixtureDocs VERSION 0.1.0)\n\
+    endif()\n";
+  write_fixture(dir.path(), "CMakeLists.txt", content.as_bytes());
+
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• CMakeLists............... 1.4.2\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    read(dir.path(), "CMakeLists.txt"),
+    content.replace("    1.4.2\n", "    1.5.0\n")
+  );
+}
+
+#[test]
+fn version_variables_and_tweaks() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"set(FIXTURE_VERSION \"1.4.2.7\" CACHE STRING \"Fixture version\")\n\
+      set(VERSION_USED ${FIXTURE_VERSION})\n\
+      project(Fixture VERSION ${VERSION_USED})\n",
+  );
+
+  // The tweak is build metadata, reset by a bump.
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• CMakeLists............... 1.4.2+7\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-patch"]));
+  assert!(read(dir.path(), "CMakeLists.txt").starts_with("set(FIXTURE_VERSION \"1.4.3.0\" CACHE"));
+  wucc(dir.path(), &args(["version", "--assign", "2.0.0+12"]));
+  assert!(read(dir.path(), "CMakeLists.txt").starts_with("set(FIXTURE_VERSION \"2.0.0.12\" CACHE"));
+
+  // Short versions keep their length unless the version needs more.
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"project(Fixture VERSION 2)\n",
+  );
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• CMakeLists............... 2.0.0\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-major"]));
+  assert_eq!(
+    read(dir.path(), "CMakeLists.txt"),
+    "project(Fixture VERSION 3)\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    read(dir.path(), "CMakeLists.txt"),
+    "project(Fixture VERSION 3.1)\n"
+  );
+}
+
+#[test]
+fn unset_version_variable_is_reported() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"project(Fixture VERSION ${FIXTURE_VERSION})\nset(FIXTURE_VERSION 1.0.0)\n",
+  );
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "1.1.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(
+    String::from_utf8(output.stderr).unwrap().contains(
+      "Variable 'FIXTURE_VERSION' used as project() VERSION is not set in CMakeLists.txt"
+    )
+  );
+}
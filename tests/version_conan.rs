@@ -0,0 +1,80 @@
+mod common;
+
+use common::{args, read, run_wucc, shown, write_fixture, wucc};
+
+#[test]
+fn recipe_class_is_found_by_inheritance() {
+  let dir = tempfile::tempdir().unwrap();
+  let recipe = r#"from conan import ConanFile as Recipe
+from helpers import BaseTool
+
+
+class Tool(BaseTool):
+    version = "0.0.1"
+
+
+class FixtureBase(Recipe):
+    """Shared settings; "version" is not set here."""
+    settings = ("os",
+                "arch")
+
+
+class FixtureConan(FixtureBase):
+    name = 'fixture'
+    version: str = '1.4.2'  # bumped on release
+"#;
+  write_fixture(dir.path(), "conanfile.py", recipe.as_bytes());
+
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• Conanfile................ 1.4.2\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    read(dir.path(), "conanfile.py"),
+    recipe.replace("'1.4.2'", "'1.5.0'")
+  );
+}
+
+#[test]
+fn version_set_in_a_method() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "conanfile.py",
+    b"import conan as c\n\
+      \n\
+      class FixtureConan(c.ConanFile):\n    \
+          def set_version(self):\n        \
+              self.version = \\\n            \"1.4.2\"\n",
+  );
+
+  wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert!(read(dir.path(), "conanfile.py").ends_with("self.version = \\\n            \"2.0.0\"\n"));
+}
+
+#[test]
+fn computed_version_is_reported() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "conanfile.py",
+    b"from conans import ConanFile\n\
+      from version import get_version\n\
+      \n\
+      class FixtureBase(ConanFile):\n    \
+          version = \"0.1.0\"\n\
+      \n\
+      class FixtureConan(FixtureBase):\n    \
+          version = get_version()\n",
+  );
+  // The recipe class computes its version, whatever its base says.
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("The version of conanfile.py is computed by `get_version()` and cannot be written")
+  );
+}
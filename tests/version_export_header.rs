@@ -0,0 +1,155 @@
+mod common;
+
+use common::{args, read, run_wucc, write_fixture, wucc};
+
+const HEADER: &str = r#"#pragma once
+#  define Core_VERSION_MAJOR   1
+#  define Core_VERSION_MINOR   4
+#  define Core_VERSION_PATCH   2
+#  define Core_VERSION_TWEAK   7
+#  define Core_VERSION_STRING  "1.4.2+7"
+
+#define net_VERSION_MAJOR 0
+#define net_VERSION_MINOR 3
+#define net_VERSION_PATCH 0
+#define net_VERSION_STRING "0.3.0"
+"#;
+
+#[test]
+fn prefixes_are_handled_independently() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(dir.path(), "include/version.h", HEADER.as_bytes());
+
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--show", "--header-prefix", "Core,net"]),
+  );
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• Export header (Core)..... 1.4.2+7\n\
+     • Export header (net)...... 0.3.0\n"
+  );
+
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-minor", "--header-prefix", "net"]),
+  );
+  let expected = HEADER
+    .replace("net_VERSION_MINOR 3", "net_VERSION_MINOR 4")
+    .replace("\"0.3.0\"", "\"0.4.0\"");
+  assert_eq!(read(dir.path(), "include/version.h"), expected);
+
+  // The tweak is build metadata, reset by a bump; the spacing is kept.
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-patch", "--header-prefix", "Core"]),
+  );
+  let expected = expected
+    .replace("PATCH   2", "PATCH   3")
+    .replace("TWEAK   7", "TWEAK   0")
+    .replace("\"1.4.2+7\"", "\"1.4.3\"");
+  assert_eq!(read(dir.path(), "include/version.h"), expected);
+}
+
+#[test]
+fn prefixes_are_paired_with_their_headers() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "include/core/version.h",
+    b"#define CORE_VERSION_STRING \"1.4.2\"\n",
+  );
+  write_fixture(
+    dir.path(),
+    "include/net/version.h",
+    b"#define NET_VERSION_STRING \"0.3.0\"\n",
+  );
+
+  // Each header only holds the version of its own prefix.
+  let output = wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--show",
+      "--all-headers",
+      "--header-prefix",
+      "CORE,NET",
+    ]),
+  );
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• Export header (include/core/version.h) 1.4.2\n\
+     • Export header (include/net/version.h) 0.3.0\n"
+  );
+
+  wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--assign",
+      "2.0.0",
+      "--all-headers",
+      "--header-prefix",
+      "NET",
+    ]),
+  );
+  assert_eq!(
+    read(dir.path(), "include/core/version.h"),
+    "#define CORE_VERSION_STRING \"1.4.2\"\n"
+  );
+  assert_eq!(
+    read(dir.path(), "include/net/version.h"),
+    "#define NET_VERSION_STRING \"2.0.0\"\n"
+  );
+}
+
+#[test]
+fn configured_prefix_and_string_only_headers() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "include/version.h",
+    b"#define FOO_VERSION_STRING \"2.1.0\"\n#define BAR_VERSION_STRING \"0.1.0\"\n",
+  );
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  sources:\n    - {kind: export-header, path: include/version.h, prefix: FOO}\n",
+  );
+
+  wucc(dir.path(), &args(["version", "--bump-patch"]));
+  assert_eq!(
+    read(dir.path(), "include/version.h"),
+    "#define FOO_VERSION_STRING \"2.1.1\"\n#define BAR_VERSION_STRING \"0.1.0\"\n"
+  );
+
+  let output = run_wucc(
+    dir.path(),
+    &args(["version", "--show", "--header-prefix", "1FOO"]),
+  );
+  assert_eq!(output.status.code(), Some(7));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("'1FOO' from the header prefixes is not a valid C++ identifier")
+  );
+}
+
+#[test]
+fn line_endings_are_kept() {
+  let dir = tempfile::tempdir().unwrap();
+  let header = "#pragma once\r\n#define APP_VERSION_MAJOR 1\r\n#define APP_VERSION_MINOR 4\r\n\
+                #define APP_VERSION_PATCH 2\r\n#define APP_VERSION_STRING \"1.4.2\"";
+  write_fixture(dir.path(), "include/version.h", header.as_bytes());
+
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-minor", "--pre", "rc"]),
+  );
+  assert_eq!(
+    read(dir.path(), "include/version.h"),
+    "#pragma once\r\n#define APP_VERSION_MAJOR 1\r\n#define APP_VERSION_MINOR 5\r\n\
+     #define APP_VERSION_PATCH 0\r\n#define APP_VERSION_PRERELEASE \"rc.1\"\r\n\
+     #define APP_VERSION_STRING \"1.5.0-rc.1\""
+  );
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{args, copy_fixture, git, read, repository, run_wucc, write_fixture, wucc};
+use std::path::Path;
+
+/// Cargo and CMake version files and a README.
+const FIXTURES: [&str; 3] = ["version/cargo", "version/cmake", "version/readme"];
+
+fn committed_files(dir: &Path) -> String {
+  git(dir, &["show", "--name-only", "--format=", "HEAD"])
+}
+
+#[test]
+fn commit_and_tag() {
+  let dir = repository(&FIXTURES);
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--bump-minor", "--commit", "--tag"]),
+  );
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("Committed 2 file(s) as "), "{}", stdout);
+  assert!(stdout.contains("Tagged v1.5.0"));
+
+  assert_eq!(
+    git(dir.path(), &["log", "-1", "--format=%s"]),
+    "Bump version to 1.5.0\n"
+  );
+  assert_eq!(committed_files(dir.path()), "CMakeLists.txt\nCargo.toml\n");
+  assert_eq!(git(dir.path(), &["cat-file", "-t", "v1.5.0"]), "tag\n");
+  assert_eq!(
+    git(dir.path(), &["rev-parse", "v1.5.0^{commit}"]),
+    git(dir.path(), &["rev-parse", "HEAD"])
+  );
+  assert!(git(dir.path(), &["status", "--porcelain"]).is_empty());
+}
+
+#[test]
+fn message_template_and_report() {
+  let dir = repository(&FIXTURES);
+  let output = wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--pre",
+      "rc",
+      "--commit",
+      "--tag",
+      "--message",
+      "release: {tag} ({version})",
+      "--format",
+      "json",
+    ]),
+  );
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+  assert_eq!(report["tag"], "v1.4.3-rc.1");
+  assert_eq!(
+    report["commit"].as_str().unwrap(),
+    git(dir.path(), &["rev-parse", "HEAD"]).trim()
+  );
+  assert_eq!(
+    git(dir.path(), &["log", "-1", "--format=%s"]),
+    "release: v1.4.3-rc.1 (1.4.3-rc.1)\n"
+  );
+}
+
+#[test]
+fn dirty_tree_is_refused_unless_forced() {
+  let dir = repository(&FIXTURES);
+  write_fixture(dir.path(), "README.md", b"# Fixture\n\nWork in progress.\n");
+
+  let output = run_wucc(dir.path(), &args(["version", "-p", "--commit"]));
+  assert_eq!(output.status.code(), Some(11));
+  assert!(String::from_utf8_lossy(&output.stderr).contains("uncommitted changes (README.md)"));
+  assert!(read(dir.path(), "Cargo.toml").contains("1.4.2"));
+
+  wucc(dir.path(), &args(["version", "-p", "--commit", "--force"]));
+  assert_eq!(committed_files(dir.path()), "CMakeLists.txt\nCargo.toml\n");
+  assert_eq!(
+    git(dir.path(), &["status", "--porcelain"]),
+    " M README.md\n"
+  );
+}
+
+#[test]
+fn existing_tag_is_refused() {
+  let dir = repository(&FIXTURES);
+  git(dir.path(), &["tag", "v1.4.3"]);
+
+  let output = run_wucc(dir.path(), &args(["version", "-p", "--commit", "--tag"]));
+  assert_eq!(output.status.code(), Some(10));
+  assert!(String::from_utf8_lossy(&output.stderr).contains("tag already exists"));
+  assert!(git(dir.path(), &["status", "--porcelain"]).is_empty());
+}
+
+#[test]
+fn outside_a_repository() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/cargo", dir.path());
+
+  let output = run_wucc(dir.path(), &args(["version", "-p", "--commit"]));
+  assert_eq!(output.status.code(), Some(10));
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::{
+  args, assert_golden, git, init_repository, project, read, run_wucc, write_fixture, wucc,
+};
+use std::{ffi::OsString, path::Path, process::Command};
+
+const HEADER: &str = "include/fixture/version.h";
+
+fn wucc_on_build_day(dir: &Path, args: &[OsString]) {
+  let output = Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(dir)
+    .env("NO_COLOR", "1")
+    .env("SOURCE_DATE_EPOCH", "1704153600")
+    .args(args)
+    .output()
+    .expect("failed to run wucc");
+  assert!(
+    output.status.success(),
+    "wucc failed:\n{}",
+    String::from_utf8_lossy(&output.stderr)
+  );
+}
+
+#[test]
+fn header_is_generated_and_kept_in_sync() {
+  let dir = project(&["version/cmake"]);
+  wucc_on_build_day(
+    dir.path(),
+    &args([
+      "version",
+      "--assign",
+      "1.5.0-rc.1",
+      "--emit-header",
+      HEADER,
+      "--prefix",
+      "FIXTURE",
+      "--header-namespace",
+      "fixture::meta",
+      "--include-guard",
+      "ifndef",
+      "--build-date",
+    ]),
+  );
+  assert_golden(
+    "version/emit-header/version.h.golden",
+    &read(dir.path(), HEADER),
+  );
+
+  // The generated header is an export header from then on.
+  let output = wucc(dir.path(), &args(["version", "--show"]));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• CMakeLists............... 1.5.0\n\
+     • Export header............ 1.5.0-rc.1\n"
+  );
+  wucc(dir.path(), &args(["version", "--release"]));
+  let header = read(dir.path(), HEADER);
+  assert!(header.contains("#define FIXTURE_VERSION_MINOR 5\n"));
+  assert!(header.contains("#define FIXTURE_VERSION_PRERELEASE \"\"\n"));
+}
+
+#[test]
+fn git_state_is_recorded() {
+  let dir = project(&["version/cmake"]);
+  init_repository(dir.path());
+  let head = git(dir.path(), &["rev-parse", "HEAD"]);
+  let emit = args([
+    "version",
+    "--get",
+    "--emit-header",
+    "version.h",
+    "--prefix",
+    "FIXTURE",
+    "--git-info",
+  ]);
+
+  let output = wucc(dir.path(), &emit);
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.4.2\n");
+  let header = read(dir.path(), "version.h");
+  assert!(header.contains(&format!(
+    "#define FIXTURE_VERSION_COMMIT \"{}\"\n",
+    head.trim()
+  )));
+  assert!(header.contains("#define FIXTURE_VERSION_DIRTY 0\n"));
+  assert!(!header.contains("namespace"));
+
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"project(fixture VERSION 1.4.2)\n",
+  );
+  wucc(dir.path(), &emit);
+  assert!(read(dir.path(), "version.h").contains("#define FIXTURE_VERSION_DIRTY 1\n"));
+}
+
+#[test]
+fn unusable_headers_are_rejected() {
+  let dir = project(&["version/cmake"]);
+  let cmake = read(dir.path(), "CMakeLists.txt");
+  let output = run_wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--bump-patch",
+      "--emit-header",
+      "version.h",
+      "--prefix",
+      "1X",
+    ]),
+  );
+  assert_eq!(output.status.code(), Some(7));
+  assert_eq!(read(dir.path(), "CMakeLists.txt"), cmake);
+
+  let output = run_wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--assign",
+      "1.256.0",
+      "--emit-header",
+      "version.h",
+      "--prefix",
+      "FIXTURE",
+    ]),
+  );
+  assert_eq!(output.status.code(), Some(4));
+  assert!(!dir.path().join("version.h").exists());
+  // Nothing is written when the header cannot be.
+  assert_eq!(read(dir.path(), "CMakeLists.txt"), cmake);
+
+  let output = run_wucc(dir.path(), &args(["version", "--emit-header", "version.h"]));
+  assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{args, read, write_fixture, wucc};
+
+fn header(prefix: &str, minor: u32) -> Vec<u8> {
+  format!(
+    "#define {0}_VERSION_MAJOR 1\n#define {0}_VERSION_MINOR {1}\n#define {0}_VERSION_PATCH 0\n",
+    prefix, minor
+  )
+  .into_bytes()
+}
+
+#[test]
+fn every_matching_header_is_updated() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(dir.path(), "include/foo_version.h", &header("FOO", 2));
+  write_fixture(dir.path(), "src/bar/version.hpp", &header("BAR", 2));
+  write_fixture(dir.path(), "config.h.in", &header("CFG", 2));
+  write_fixture(dir.path(), "build/foo_version.h", &header("FOO", 0));
+  write_fixture(dir.path(), ".gitignore", b"/build/\n");
+  // Build directories are skipped even when nothing ignores them.
+  write_fixture(dir.path(), "out/foo_version.h", &header("FOO", 0));
+
+  let search = [
+    "--header-name",
+    "*_version.h,version.hpp,config.h.in",
+    "--all-headers",
+  ];
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--show"].iter().chain(&search)),
+  );
+  // Search directories come in turn, each walked in path order; ignored files are skipped.
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• Export header (include/foo_version.h) 1.2.0\n\
+     • Export header (src/bar/version.hpp) 1.2.0\n\
+     • Export header (config.h.in) 1.2.0\n"
+  );
+
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-minor"].iter().chain(&search)),
+  );
+  assert!(read(dir.path(), "include/foo_version.h").contains("FOO_VERSION_MINOR 3"));
+  assert!(read(dir.path(), "src/bar/version.hpp").contains("BAR_VERSION_MINOR 3"));
+  assert!(read(dir.path(), "config.h.in").contains("CFG_VERSION_MINOR 3"));
+  assert_eq!(
+    read(dir.path(), "build/foo_version.h").into_bytes(),
+    header("FOO", 0)
+  );
+  assert_eq!(
+    read(dir.path(), "out/foo_version.h").into_bytes(),
+    header("FOO", 0)
+  );
+}
+
+#[test]
+fn configured_search_takes_the_first_header() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(dir.path(), "include/version.h", &header("TOP", 0));
+  write_fixture(dir.path(), "lib/b/version.hpp", &header("LIBB", 2));
+  write_fixture(dir.path(), "lib/a/version.hpp", &header("LIBA", 1));
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  headers:\n    names: [version.hpp]\n    dirs: [lib]\n",
+  );
+
+  let output = wucc(dir.path(), &args(["version", "--show", "--format", "json"]));
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  let files = report["files"].as_array().unwrap();
+  assert_eq!(files.len(), 1);
+  assert_eq!(files[0]["path"], "lib/a/version.hpp");
+  assert_eq!(files[0]["old_version"], "1.1.0");
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{args, read, run_wucc, shown, write_fixture, wucc};
+
+#[test]
+fn returned_table_with_nested_version() {
+  let dir = tempfile::tempdir().unwrap();
+  let metafile = r#"-- version = "0.0.0" in a comment
+--[[ and version = "0.0.1"
+     in a long one ]]
+return {
+  name = 'fixture',
+  dependencies = {
+    { name = "core", version = "2.0.0" },
+  },
+  version = '1.4.2',
+}
+"#;
+  write_fixture(dir.path(), "meta.lua", metafile.as_bytes());
+
+  // The dependency comes first, but is not a field of the returned table.
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• Plugin metafile (lua).... 1.4.2\n"
+  );
+  wucc(dir.path(), &args(["version", "--bump-patch"]));
+  assert_eq!(
+    read(dir.path(), "meta.lua"),
+    metafile.replace("'1.4.2'", "'1.4.3'")
+  );
+}
+
+#[test]
+fn nested_versions_are_not_the_plugin_version() {
+  let dir = tempfile::tempdir().unwrap();
+  let metafile = "return {\n  dependencies = { { name = \"core\", version = \"2.0.0\" } },\n  meta = { version = '1.4.2' },\n}\n";
+  write_fixture(dir.path(), "meta.lua", metafile.as_bytes());
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "3.0.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert_eq!(read(dir.path(), "meta.lua"), metafile);
+
+  // A nested version is found at its configured key.
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  detect: false\n  sources:\n    - {kind: lua-metafile, path: meta.lua, key: meta.version}\n",
+  );
+  wucc(dir.path(), &args(["version", "--assign", "3.0.0"]));
+  assert_eq!(
+    read(dir.path(), "meta.lua"),
+    metafile.replace("'1.4.2'", "'3.0.0'")
+  );
+}
+
+#[test]
+fn long_strings_and_assignments() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "meta.lua",
+    b"local plugin = {}\nplugin.name = \"fixture\"\nplugin.version = [[1.4.2]]\nreturn plugin\n",
+  );
+  wucc(dir.path(), &args(["version", "--assign", "2.0.0-rc.1"]));
+  assert_eq!(
+    read(dir.path(), "meta.lua"),
+    "local plugin = {}\nplugin.name = \"fixture\"\nplugin.version = [[2.0.0-rc.1]]\nreturn plugin\n"
+  );
+
+  // The last field of a table needs no trailing comma.
+  write_fixture(
+    dir.path(),
+    "meta.lua",
+    b"plugin = { name = \"fixture\"; version = \"1.0.0\" }\n",
+  );
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• Plugin metafile (lua).... 1.0.0\n"
+  );
+}
+
+#[test]
+fn configured_key() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "meta.lua",
+    b"return {\n  version = \"0.1.0\", -- format version\n  plugin = { version = \"1.4.2\" },\n}\n",
+  );
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  detect: false\n  sources:\n    - {kind: lua-metafile, path: meta.lua, key: plugin.version}\n",
+  );
+
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    read(dir.path(), "meta.lua"),
+    "return {\n  version = \"0.1.0\", -- format version\n  plugin = { version = \"1.5.0\" },\n}\n"
+  );
+
+  write_fixture(dir.path(), "meta.lua", b"return { name = \"fixture\" }\n");
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("Version field 'plugin.version' not found in meta.lua")
+  );
+}
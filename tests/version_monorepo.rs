@@ -0,0 +1,148 @@
+mod common;
+
+use common::{args, git, init_repository, project, read, run_wucc, write_fixture, wucc};
+
+#[test]
+fn components_are_listed_as_a_tree() {
+  let dir = project(&["version/monorepo"]);
+  write_fixture(
+    dir.path(),
+    "third_party/zlib/CMakeLists.txt",
+    b"project(zlib VERSION 1.3.1)\n",
+  );
+  write_fixture(dir.path(), ".gitignore", b"/third_party/\n");
+  let output = wucc(dir.path(), &args(["version", "--components"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  // `apps/cli/build` and the ignored `third_party` are skipped, and the header
+  // of `core` stays with it.
+  assert_eq!(
+    stdout,
+    ". 0.1.0\n\
+     └─ npm package.............. 0.1.0\n\
+     cli (apps/cli) 2.0.0\n\
+     └─ npm package.............. 2.0.0\n\
+     core (libs/core) 1.4.2\n\
+     ├─ Cargo manifest........... 1.4.2\n\
+     └─ Export header............ 1.4.2\n\
+     net (libs/net) 0.3.0\n\
+     └─ CMakeLists............... 0.3.0\n"
+  );
+
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--components", "--format", "json"]),
+  );
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  let components = report["components"].as_array().unwrap();
+  assert_eq!(components.len(), 4);
+  assert_eq!(components[2]["name"], "core");
+  assert_eq!(components[2]["path"], "libs/core");
+  assert_eq!(
+    components[2]["files"][1]["path"],
+    "libs/core/include/core/version.h"
+  );
+}
+
+#[test]
+fn bump_touches_only_the_component() {
+  let dir = project(&["version/monorepo"]);
+  let cli = read(dir.path(), "apps/cli/package.json");
+  let net = read(dir.path(), "libs/net/CMakeLists.txt");
+
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--bump-patch", "--component", "core", "--get"]),
+  );
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.4.3\n");
+  assert!(read(dir.path(), "libs/core/Cargo.toml").contains("version = \"1.4.3\""));
+  assert!(read(dir.path(), "libs/core/include/core/version.h").contains("PATCH 3"));
+  assert_eq!(read(dir.path(), "apps/cli/package.json"), cli);
+  assert_eq!(read(dir.path(), "libs/net/CMakeLists.txt"), net);
+
+  // Components can also be addressed by path.
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--show", "--component", "libs/net"]),
+  );
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• CMakeLists............... 0.3.0\n"
+  );
+
+  let output = run_wucc(dir.path(), &args(["version", "-s", "--component", "ui"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("Component 'ui' not found; components: ., cli, core, net")
+  );
+}
+
+#[test]
+fn sources_belong_to_their_component() {
+  let dir = project(&["version/monorepo"]);
+  write_fixture(dir.path(), "libs/net/Doxyfile", b"PROJECT_NUMBER = 0.3.0\n");
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  sources:\n    - kind: regex\n      path: libs/net/Doxyfile\n      pattern: 'PROJECT_NUMBER = (\\S+)'\n",
+  );
+
+  // The root holds every path, but not those of the components below it.
+  let output = wucc(dir.path(), &args(["version", "--show", "--component", "."]));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• npm package.............. 0.1.0\n"
+  );
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--show", "--component", "net"]),
+  );
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• CMakeLists............... 0.3.0\n\
+     • Pattern.................. 0.3.0\n"
+  );
+}
+
+#[test]
+fn component_tags_and_history() {
+  let dir = project(&["version/monorepo"]);
+  init_repository(dir.path());
+  git(dir.path(), &["tag", "core/v1.4.2"]);
+  write_fixture(dir.path(), "libs/net/src/net.cpp", b"// sockets\n");
+  git(dir.path(), &["add", "--all"]);
+  git(
+    dir.path(),
+    &["commit", "--quiet", "-m", "feat(net): add sockets"],
+  );
+
+  // The feature only touches `net`, so `core` has nothing to release.
+  let output = wucc(
+    dir.path(),
+    &args(["version", "--auto", "--component", "core"]),
+  );
+  assert!(
+    String::from_utf8(output.stdout)
+      .unwrap()
+      .contains("nothing to release")
+  );
+
+  wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--auto",
+      "--component",
+      "net",
+      "--commit",
+      "--tag",
+    ]),
+  );
+  assert!(read(dir.path(), "libs/net/CMakeLists.txt").contains("VERSION 0.4.0"));
+  assert_eq!(
+    git(dir.path(), &["tag", "--list", "net/*"]).trim(),
+    "net/v0.4.0"
+  );
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{args, project, run_wucc, write_fixture, wucc};
+use serde_json::{Value, json};
+use std::path::Path;
+
+fn stdout_json(dir: &Path, extra: &[&str]) -> Value {
+  let output = run_wucc(dir, &args(extra));
+  serde_json::from_slice(&output.stdout)
+    .unwrap_or_else(|e| panic!("{}: {}", e, String::from_utf8_lossy(&output.stdout)))
+}
+
+#[test]
+fn show_as_json() {
+  let dir = project(&["version/cargo", "version/header"]);
+  let report = stdout_json(dir.path(), &["version", "--show", "--format", "json"]);
+
+  assert_eq!(
+    report,
+    json!({
+      "version": "1.4.2",
+      "files": [
+        {
+          "kind": "cargo",
+          "name": "Cargo manifest",
+          "path": "Cargo.toml",
+          "old_version": "1.4.2",
+          "new_version": null,
+          "status": "read",
+          "error": null,
+        },
+        {
+          "kind": "export-header",
+          "name": "Export header",
+          "path": "include/version.h",
+          "old_version": "1.4.2",
+          "new_version": null,
+          "status": "read",
+          "error": null,
+        },
+      ],
+    })
+  );
+}
+
+#[test]
+fn show_as_yaml() {
+  let dir = project(&["version/cargo", "version/header"]);
+  let output = wucc(dir.path(), &args(["version", "-s", "-f", "yaml"]));
+  let report: serde_norway::Value = serde_norway::from_slice(&output.stdout).unwrap();
+
+  assert_eq!(report["version"].as_str(), Some("1.4.2"));
+  assert_eq!(report["files"][1]["kind"].as_str(), Some("export-header"));
+}
+
+#[test]
+fn bump_as_json_reports_each_file() {
+  let dir = project(&["version/cargo", "version/header"]);
+  write_fixture(
+    dir.path(),
+    "CMakeLists.txt",
+    b"project(Fixture LANGUAGES CXX)\n",
+  );
+  let report = stdout_json(dir.path(), &["version", "--bump-minor", "--format", "json"]);
+
+  assert_eq!(report["version"], "1.5.0");
+  let statuses: Vec<(&str, &str)> = report["files"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|file| {
+      (
+        file["kind"].as_str().unwrap(),
+        file["status"].as_str().unwrap(),
+      )
+    })
+    .collect();
+  assert_eq!(
+    statuses,
+    [
+      ("cargo", "updated"),
+      ("cmake", "failed"),
+      ("export-header", "updated")
+    ]
+  );
+  assert!(
+    report["files"][1]["error"]
+      .as_str()
+      .unwrap()
+      .contains("VERSION field not found")
+  );
+}
+
+#[test]
+fn get_prints_only_the_version() {
+  let dir = project(&["version/cargo", "version/header"]);
+
+  let output = wucc(dir.path(), &args(["version", "--get"]));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.4.2\n");
+
+  let output = wucc(dir.path(), &args(["version", "--bump-patch", "--get"]));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.4.3\n");
+  let output = wucc(dir.path(), &args(["version", "-g"]));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.4.3\n");
+
+  let empty = tempfile::tempdir().unwrap();
+  let output = run_wucc(empty.path(), &args(["version", "--get"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{args, project, read, wucc};
+use semver::Version;
+use wucc::version::{Bump, bump, next_prerelease, with_build};
+
+fn v(version: &str) -> Version {
+  Version::parse(version).unwrap()
+}
+
+#[test]
+fn version_arithmetic() {
+  assert_eq!(
+    next_prerelease(&v("1.2.0-rc.3"), "rc").unwrap(),
+    v("1.2.0-rc.4")
+  );
+  assert_eq!(
+    next_prerelease(&v("1.2.0-beta.2"), "rc").unwrap(),
+    v("1.2.0-rc.1")
+  );
+  assert_eq!(
+    next_prerelease(&v("1.2.0-rc"), "rc").unwrap(),
+    v("1.2.0-rc.1")
+  );
+  assert_eq!(
+    next_prerelease(&v("1.2.0"), "alpha").unwrap(),
+    v("1.2.1-alpha.1")
+  );
+  assert_eq!(bump(&v("1.2.0-rc.3+abc"), Bump::Minor), v("1.3.0"));
+  assert_eq!(
+    with_build(&v("1.2.0-rc.3"), "ci.42").unwrap(),
+    v("1.2.0-rc.3+ci.42")
+  );
+  assert!(next_prerelease(&v("1.2.0"), "not valid").is_err());
+
+  // Pre-releases never go backwards.
+  let error = next_prerelease(&v("1.2.0-rc.3"), "beta").unwrap_err();
+  assert!(
+    error
+      .to_string()
+      .contains("pre-release 1.2.0-beta.1 would come before 1.2.0-rc.3"),
+    "{}",
+    error
+  );
+}
+
+#[test]
+fn release_train() {
+  let dir = project(&["version/cargo", "version/cmake", "version/header"]);
+
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-minor", "--pre", "rc"]),
+  );
+  assert!(read(dir.path(), "Cargo.toml").contains("version = \"1.5.0-rc.1\""));
+  assert!(read(dir.path(), "CMakeLists.txt").contains("VERSION 1.5.0 "));
+  assert!(
+    read(dir.path(), "include/version.h")
+      .contains("#define FIXTURE_VERSION_PATCH 0\n#define FIXTURE_VERSION_PRERELEASE \"rc.1\"\n")
+  );
+
+  let output = wucc(dir.path(), &args(["version", "--pre", "rc", "--get"]));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.5.0-rc.2\n");
+  assert!(read(dir.path(), "include/version.h").contains("PRERELEASE \"rc.2\""));
+
+  // CMake only keeps the numeric core, which still counts as in sync.
+  wucc(dir.path(), &args(["version", "--check"]));
+
+  let output = wucc(dir.path(), &args(["version", "--release", "--get"]));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "1.5.0\n");
+  assert!(read(dir.path(), "include/version.h").contains("PRERELEASE \"\""));
+  let output = wucc(dir.path(), &args(["version", "--show"]));
+  assert!(!String::from_utf8(output.stdout).unwrap().contains("rc"));
+}
+
+#[test]
+fn build_metadata() {
+  let dir = project(&["version/cargo", "version/cmake", "version/header"]);
+  wucc(dir.path(), &args(["version", "--build", "ci.7"]));
+
+  assert!(read(dir.path(), "Cargo.toml").contains("version = \"1.4.2+ci.7\""));
+  assert!(read(dir.path(), "CMakeLists.txt").contains("VERSION 1.4.2 "));
+  wucc(
+    dir.path(),
+    &args(["version", "--check", "--reference", "cargo"]),
+  );
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{args, project, read, wucc};
+use std::{
+  io::Write,
+  path::Path,
+  process::{Command, Stdio},
+};
+
+fn snapshot(dir: &Path) -> [String; 2] {
+  ["Cargo.toml", "meta.yml"].map(|file| read(dir, file))
+}
+
+#[test]
+fn dry_run_prints_diffs_without_writing() {
+  let dir = project(&["version/cargo", "version/yaml"]);
+  let before = snapshot(dir.path());
+
+  let output = wucc(dir.path(), &args(["version", "--bump-minor", "--dry-run"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  assert_eq!(snapshot(dir.path()), before);
+  assert!(
+    stdout.contains("--- a/Cargo.toml\n+++ b/Cargo.toml\n"),
+    "{}",
+    stdout
+  );
+  assert!(stdout.contains("-version = \"1.4.2\"\n+version = \"1.5.0\"\n"));
+  assert!(stdout.contains("-version: 1.4.2\n+version: 1.5.0\n"));
+  assert!(stdout.ends_with("Dry run: no files were changed.\n"));
+}
+
+#[test]
+fn dry_run_report_includes_diffs() {
+  let dir = project(&["version/cargo", "version/yaml"]);
+  let output = wucc(
+    dir.path(),
+    &args(["version", "-a", "1.4.2", "-n", "--format", "json"]),
+  );
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(report["files"][0]["status"], "unchanged");
+  assert_eq!(report["files"][0]["diff"], "");
+
+  let output = wucc(dir.path(), &args(["version", "-p", "-n", "-f", "json"]));
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(report["files"][1]["kind"], "cargo");
+  assert_eq!(report["files"][1]["status"], "would-update");
+  assert!(
+    report["files"][1]["diff"]
+      .as_str()
+      .unwrap()
+      .contains("+version = \"1.4.3\"")
+  );
+}
+
+#[test]
+fn interactive_writes_only_confirmed_files() {
+  let dir = project(&["version/cargo", "version/yaml"]);
+  let mut child = Command::new(env!("CARGO_BIN_EXE_wucc"))
+    .current_dir(dir.path())
+    .env("NO_COLOR", "1")
+    .args(["version", "--bump-major", "--interactive"])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap();
+  // Files are visited in detection order: the plugin metafile comes first.
+  child.stdin.take().unwrap().write_all(b"n\ny\n").unwrap();
+  let output = child.wait_with_output().unwrap();
+  assert!(output.status.success());
+
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("Apply changes to meta.yml? [y/N]"));
+  assert!(stdout.contains("Plugin metafile.......... 1.4.2 (skipped)"));
+  assert!(stdout.contains("Cargo manifest........... 1.4.2 -> 2.0.0"));
+
+  let [manifest, metafile] = snapshot(dir.path());
+  assert!(manifest.contains("version = \"2.0.0\""));
+  assert!(metafile.contains("version: 1.4.2"));
+}
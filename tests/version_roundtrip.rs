@@ -0,0 +1,231 @@
+mod common;
+
+use common::{args, assert_golden, copy_fixture, manifest_dir, run_wucc, write_fixture, wucc};
+use std::path::Path;
+
+/// Version file formats: fixture directory, version file inside it and its name in `wucc version` output.
+const FORMATS: [(&str, &str, &str); 13] = [
+  ("cargo", "Cargo.toml", "Cargo manifest"),
+  ("cmake", "CMakeLists.txt", "CMakeLists"),
+  ("conan", "conanfile.py", "Conanfile"),
+  ("conandata", "conandata.yml", "Conan data"),
+  ("meson", "meson.build", "Meson project"),
+  ("vcpkg", "vcpkg.json", "vcpkg manifest"),
+  ("yaml", "meta.yml", "Plugin metafile"),
+  ("lua", "meta.lua", "Plugin metafile (lua)"),
+  ("npm", "package.json", "npm package"),
+  ("npm-lock", "package-lock.json", "npm lockfile"),
+  ("pyproject", "pyproject.toml", "Python project"),
+  ("setup-cfg", "setup.cfg", "setuptools config"),
+  ("header", "include/version.h", "Export header"),
+];
+
+fn shown_version(dir: &Path, name: &str) -> String {
+  let output = wucc(dir, &args(["version", "--show"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  let line = stdout
+    .lines()
+    .find(|line| line.starts_with(&format!("• {}.", name)))
+    .unwrap_or_else(|| panic!("{} is missing from:\n{}", name, stdout));
+  line.rsplit(' ').next().unwrap().to_string()
+}
+
+#[test]
+fn formats_are_read() {
+  for (fixture, _, name) in FORMATS {
+    let dir = tempfile::tempdir().unwrap();
+    copy_fixture(&format!("version/{}", fixture), dir.path());
+
+    assert_eq!(shown_version(dir.path(), name), "1.4.2", "{}", fixture);
+  }
+}
+
+#[test]
+fn formats_are_written() {
+  for (fixture, file, name) in FORMATS {
+    let dir = tempfile::tempdir().unwrap();
+    copy_fixture(&format!("version/{}", fixture), dir.path());
+
+    wucc(dir.path(), &args(["version", "--assign", "3.10.0"]));
+
+    assert_eq!(shown_version(dir.path(), name), "3.10.0", "{}", fixture);
+    assert_golden(
+      &format!("version/{}/{}.golden", fixture, file),
+      &std::fs::read_to_string(dir.path().join(file)).unwrap(),
+    );
+  }
+}
+
+#[test]
+fn formats_round_trip() {
+  for (fixture, file, name) in FORMATS {
+    let dir = tempfile::tempdir().unwrap();
+    copy_fixture(&format!("version/{}", fixture), dir.path());
+    let original = std::fs::read_to_string(dir.path().join(file)).unwrap();
+
+    wucc(dir.path(), &args(["version", "--assign", "3.10.0"]));
+    wucc(dir.path(), &args(["version", "--assign", "1.4.2"]));
+
+    assert_eq!(shown_version(dir.path(), name), "1.4.2", "{}", fixture);
+    if fixture != "vcpkg" {
+      // vcpkg manifests get their port-version reset, every other format is edited in place.
+      assert_eq!(
+        std::fs::read_to_string(dir.path().join(file)).unwrap(),
+        original,
+        "{} did not survive a round trip",
+        fixture
+      );
+    }
+  }
+}
+
+#[test]
+fn setup_cfg_line_endings_are_kept() {
+  let dir = tempfile::tempdir().unwrap();
+  let config =
+    "[metadata]\r\nname = fixture\r\nversion = 1.4.2\r\n\r\n[options]\r\npackages = find:";
+  write_fixture(dir.path(), "setup.cfg", config.as_bytes());
+
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("setup.cfg")).unwrap(),
+    config.replace("1.4.2", "1.5.0")
+  );
+}
+
+#[test]
+fn bumps_apply_to_every_format() {
+  let dir = tempfile::tempdir().unwrap();
+  for (fixture, _, _) in FORMATS {
+    copy_fixture(&format!("version/{}", fixture), dir.path());
+  }
+
+  for (flag, expected) in [("-p", "1.4.3"), ("-m", "1.5.0"), ("-M", "2.0.0")] {
+    wucc(dir.path(), &args(["version", flag]));
+    for (fixture, _, name) in FORMATS {
+      assert_eq!(
+        shown_version(dir.path(), name),
+        expected,
+        "{} after {}",
+        fixture,
+        flag
+      );
+    }
+  }
+}
+
+#[test]
+fn poetry_project() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/poetry", dir.path());
+  let original = std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap();
+
+  wucc(dir.path(), &args(["version", "--assign", "3.10.0"]));
+
+  assert_eq!(shown_version(dir.path(), "Python project"), "3.10.0");
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap(),
+    original.replace("version = '1.4.2'", "version = '3.10.0'")
+  );
+}
+
+#[test]
+fn dynamic_python_version_is_not_written() {
+  let dir = tempfile::tempdir().unwrap();
+  let content = "[project]\nname = \"fixture\"\ndynamic = [\"version\"]\n";
+  write_fixture(dir.path(), "pyproject.toml", content.as_bytes());
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "3.10.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap(),
+    content
+  );
+}
+
+#[test]
+fn numeric_json_version_is_not_written() {
+  let dir = tempfile::tempdir().unwrap();
+  let content = "{\n  \"name\": \"fixture\",\n  \"version\": 1\n}\n";
+  write_fixture(dir.path(), "package.json", content.as_bytes());
+
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "1.2.3"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("package.json")).unwrap(),
+    content
+  );
+}
+
+#[test]
+fn vcpkg_port_version_is_kept_for_the_same_version() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/vcpkg", dir.path());
+  let original = std::fs::read_to_string(dir.path().join("vcpkg.json")).unwrap();
+
+  wucc(dir.path(), &args(["version", "--assign", "1.4.2"]));
+  wucc(dir.path(), &args(["version", "--check"]));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("vcpkg.json")).unwrap(),
+    original
+  );
+}
+
+#[test]
+fn vcpkg_relaxed_version_keeps_the_numeric_core() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/cargo", dir.path());
+  let manifest = "{\n  \"name\": \"fixture\",\n  \"version\": \"1.4.2\"\n}\n";
+  write_fixture(dir.path(), "vcpkg.json", manifest.as_bytes());
+
+  wucc(
+    dir.path(),
+    &args(["version", "--bump-minor", "--pre", "rc"]),
+  );
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("vcpkg.json")).unwrap(),
+    manifest.replace("1.4.2", "1.5.0")
+  );
+  assert_eq!(shown_version(dir.path(), "Cargo manifest"), "1.5.0-rc.1");
+  wucc(dir.path(), &args(["version", "--check"]));
+}
+
+#[test]
+fn conan_set_version() {
+  let dir = tempfile::tempdir().unwrap();
+  let recipe = "from conan import ConanFile\n\n\nclass FixtureConan(ConanFile):\n    name = 'fixture'\n\n    def set_version(self):\n        self.version = '1.4.2'\n";
+  write_fixture(dir.path(), "conanfile.py", recipe.as_bytes());
+
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("conanfile.py")).unwrap(),
+    recipe.replace("'1.4.2'", "'1.5.0'")
+  );
+}
+
+#[test]
+fn conan_version_from_conandata() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/conandata", dir.path());
+  let recipe = "from conan import ConanFile\n\n\nclass FixtureConan(ConanFile):\n    name = \"fixture\"\n\n    def set_version(self):\n        self.version = self.conan_data[\"version\"]\n";
+  write_fixture(dir.path(), "conanfile.py", recipe.as_bytes());
+
+  let output = wucc(dir.path(), &args(["version", "--show"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(!stdout.contains("Conanfile"), "{}", stdout);
+
+  wucc(dir.path(), &args(["version", "--bump-patch"]));
+  assert_eq!(shown_version(dir.path(), "Conan data"), "1.4.3");
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("conanfile.py")).unwrap(),
+    recipe
+  );
+}
+
+#[test]
+fn fixtures_are_not_detected_in_this_repository() {
+  let output = wucc(manifest_dir(), &args(["version", "--show"]));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  assert!(!stdout.contains("1.4.2"), "{}", stdout);
+}
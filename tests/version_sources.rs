@@ -0,0 +1,143 @@
+mod common;
+
+use common::{args, project, read, run_wucc, shown, write_fixture, wucc};
+
+#[test]
+fn configured_sources_are_updated() {
+  let dir = project(&["version/sources"]);
+  assert_eq!(
+    shown(dir.path(), &[]),
+    "• CMakeLists............... 1.4.2\n\
+     • README badge............. 1.4.2\n\
+     • Pattern (docs/Doxyfile).. 1.4.2\n"
+  );
+
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  let readme = read(dir.path(), "README.md");
+  assert!(readme.contains("badge/version-1.5.0-blue"));
+  // Only the pattern's capture is replaced.
+  assert!(readme.contains("Install `fixture` 1.4.2"));
+  assert!(read(dir.path(), "docs/Doxyfile").contains("PROJECT_NUMBER         = 1.5.0\n"));
+  assert!(read(dir.path(), "package.json").contains("\"version\": \"1.4.2\""));
+  wucc(dir.path(), &args(["version", "--check"]));
+}
+
+#[test]
+fn command_line_selects_files() {
+  let dir = project(&["version/sources"]);
+  assert_eq!(
+    shown(dir.path(), &["--only", "cmake,regex", "--exclude", "cmake"]),
+    "• README badge............. 1.4.2\n\
+     • Pattern (docs/Doxyfile).. 1.4.2\n"
+  );
+
+  // `--file` replaces detection and the configuration.
+  write_fixture(
+    dir.path(),
+    "lib/CMakeLists.txt",
+    b"project(lib VERSION 0.2.0)\n",
+  );
+  assert_eq!(
+    shown(
+      dir.path(),
+      &[
+        "--file",
+        "cmake:lib/CMakeLists.txt",
+        "--file",
+        "npm:package.json"
+      ]
+    ),
+    "• CMakeLists............... 0.2.0\n\
+     • npm package.............. 1.4.2\n"
+  );
+  wucc(
+    dir.path(),
+    &args([
+      "version",
+      "--assign",
+      "0.3.0",
+      "--file",
+      "cmake:lib/CMakeLists.txt",
+    ]),
+  );
+  assert_eq!(
+    read(dir.path(), "lib/CMakeLists.txt"),
+    "project(lib VERSION 0.3.0)\n"
+  );
+  assert!(read(dir.path(), "CMakeLists.txt").contains("VERSION 1.4.2"));
+
+  // A regex source without a pattern looks for a version assignment.
+  assert_eq!(
+    shown(dir.path(), &["--file", "regex:package.json"]),
+    "• Pattern.................. 1.4.2\n"
+  );
+}
+
+#[test]
+fn patterns_update_the_version_they_read() {
+  let dir = project(&["version/sources"]);
+  write_fixture(
+    dir.path(),
+    "notes.txt",
+    b"version = 1.4.2\nrequires zlib version 1.3.1\n",
+  );
+  wucc(
+    dir.path(),
+    &args(["version", "--assign", "1.5.0", "--file", "regex:notes.txt"]),
+  );
+  // Without a pattern, only the first assignment is the project's.
+  assert_eq!(
+    read(dir.path(), "notes.txt"),
+    "version = 1.5.0\nrequires zlib version 1.3.1\n"
+  );
+
+  // A declared pattern updates every match, which must agree.
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  sources:\n    - kind: regex\n      path: notes.txt\n      pattern: 'version\\D*(\\S+)'\n",
+  );
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "1.6.0"]));
+  assert_eq!(output.status.code(), Some(4));
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(
+    stderr.contains("matches both 1.5.0 and 1.3.1"),
+    "{}",
+    stderr
+  );
+  assert!(read(dir.path(), "notes.txt").contains("version = 1.5.0\n"));
+}
+
+#[test]
+fn invalid_sources_are_rejected() {
+  let dir = project(&["version/sources"]);
+  let output = run_wucc(dir.path(), &args(["version", "-s", "--file", "cmake"]));
+  assert_eq!(output.status.code(), Some(2));
+  let output = run_wucc(dir.path(), &args(["version", "-s", "--only", "java"]));
+  assert_eq!(output.status.code(), Some(2));
+
+  for (config, message) in [
+    (
+      "version:\n  sources:\n    - kind: regex\n      path: README.md\n      pattern: 'version-\\S+'\n",
+      "has no capture group",
+    ),
+    (
+      "version:\n  sources:\n    - kind: cmake\n      path: CMakeLists.txt\n      pattern: '(.*)'\n",
+      "is not a regex source",
+    ),
+    (
+      "version:\n  exclude: [java]\n",
+      "unknown version file kind 'java'",
+    ),
+  ] {
+    write_fixture(dir.path(), "wucc.yml", config.as_bytes());
+    let output = run_wucc(dir.path(), &args(["version", "--show"]));
+    assert_eq!(output.status.code(), Some(4), "{}", config);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(message), "{}", stderr);
+  }
+
+  write_fixture(dir.path(), "wucc.yml", b"version:\n  sources: 3\n");
+  let output = run_wucc(dir.path(), &args(["version", "--show"]));
+  assert_eq!(output.status.code(), Some(4));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{args, assert_golden, copy_fixture, run_wucc, write_fixture, wucc};
+
+#[test]
+fn nested_key_is_edited_in_place() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/yaml-nested", dir.path());
+
+  let output = wucc(dir.path(), &args(["version", "--show"]));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "• Plugin metafile.......... 1.4.2\n"
+  );
+
+  // `plugin.host.version` is another key with the same name.
+  wucc(dir.path(), &args(["version", "--bump-minor"]));
+  assert_golden(
+    "version/yaml-nested/plugin.yml.golden",
+    &std::fs::read_to_string(dir.path().join("plugin.yml")).unwrap(),
+  );
+}
+
+#[test]
+fn comments_and_quoting_are_kept() {
+  let dir = tempfile::tempdir().unwrap();
+  write_fixture(
+    dir.path(),
+    "meta.yml",
+    b"# Fixture plugin\nname: 'Fixture'\nversion: '1.4.2' # released\ntags:\n  - version: 0.1.0\n",
+  );
+
+  wucc(dir.path(), &args(["version", "--assign", "2.0.0-rc.1"]));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("meta.yml")).unwrap(),
+    "# Fixture plugin\nname: 'Fixture'\nversion: '2.0.0-rc.1' # released\ntags:\n  - version: 0.1.0\n"
+  );
+
+  // Flow mappings are re-serialized rather than left behind.
+  write_fixture(dir.path(), "meta.yml", b"{name: Fixture, version: 2.0.0}\n");
+  wucc(dir.path(), &args(["version", "--assign", "2.1.0"]));
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("meta.yml")).unwrap(),
+    "name: Fixture\nversion: 2.1.0\n"
+  );
+}
+
+#[test]
+fn key_requires_a_metafile_source() {
+  let dir = tempfile::tempdir().unwrap();
+  copy_fixture("version/yaml-nested", dir.path());
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  sources:\n    - {kind: cmake, path: plugin.yml, key: plugin.version}\n",
+  );
+
+  let output = run_wucc(dir.path(), &args(["version", "--show"]));
+  assert_eq!(output.status.code(), Some(4));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("'plugin.yml' is not a metafile source and takes no key")
+  );
+
+  // A missing key is reported with its path.
+  write_fixture(
+    dir.path(),
+    "wucc.yml",
+    b"version:\n  detect: false\n  sources:\n    - {kind: metafile, path: plugin.yml, key: plugin.release}\n",
+  );
+  let output = run_wucc(dir.path(), &args(["version", "--assign", "2.0.0"]));
+  assert_eq!(output.status.code(), Some(5));
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("Version field 'plugin.release' not found in plugin metafile")
+  );
+}